@@ -0,0 +1,12 @@
+#![allow(unused_variables, unused_mut)]
+#![allow(unused_imports, dead_code)]
+
+
+// external modules
+extern crate strum;
+#[macro_use]
+extern crate strum_macros;
+
+
+// local modules
+pub mod mahjong;
@@ -0,0 +1,114 @@
+use crate::mahjong::*;
+
+/// how many of an opponent's first discards are read as tells. Tiles close to them are a little less likely to be waited on
+const NUM_EARLY_DISCARDS : usize = 6;
+
+/// called sets after which an opponent is assumed to be tenpai, whatever the turn
+const TENPAI_NUM_CALLED_SETS : usize = 3;
+
+/// An opponent with two called sets is assumed to be tenpai once they've discarded this many tiles
+const LATE_NUM_DISCARDS : usize = 9;
+
+/// every tile the opponent discarded, including the ones other players called
+fn all_discards(opponent : &Player) -> impl Iterator<Item = &Tile>
+{
+    opponent.discard_pile.iter().chain(opponent.tiles_others_called.iter())
+}
+
+/// true if the opponent is in riichi, or has called enough sets to probably be tenpai
+pub fn is_threat(opponent : &Player) -> bool
+{
+    opponent.is_in_riichi()
+    || opponent.called_sets.len() >= TENPAI_NUM_CALLED_SETS
+    || (opponent.called_sets.len() == TENPAI_NUM_CALLED_SETS - 1 && opponent.discard_pile.len() >= LATE_NUM_DISCARDS)
+}
+
+/// Estimates how likely the opponent is to be waiting on a tile, from 0 (genbutsu, which they can't ron) upwards.
+/// seen_counts are every tile the player discarding can see, including their own hand. They're used for kabe (all
+/// four of a tile seen, so no ryanmen can use it) and for how many of a tile are left for a tanki or shanpon
+pub fn tile_danger(tile : Tile, opponent : &Player, seen_counts : &[u8; NUM_TILE_KINDS]) -> u32
+{
+    // genbutsu. A player is furiten on any tile they discarded
+    if all_discards(opponent).any(|discard| *discard == tile)
+    {   return 0;   }
+
+    let num_unseen = 4 - seen_counts[tile.kind_idx()].min(4);
+
+    // honors can only be won on with a tanki or shanpon, which needs the unseen copies in the opponent's hand
+    if tile.suit == Suit::Honor
+    {
+        return match num_unseen {
+            0 | 1 => 1,
+            2 => 3,
+            _ => 5,
+        };
+    }
+
+    let value = tile.value as usize;
+    let suit_start_idx = tile.kind_idx() + 1 - value;
+    let discarded = |value : usize| all_discards(opponent).any(|discard| discard.kind_idx() == suit_start_idx + value - 1);
+    let walled = |value : usize| seen_counts[suit_start_idx + value - 1] >= 4;
+
+    // a ryanmen waiting on the tile is either the two tiles above it or the two below. Each side is ruled out if the
+    // opponent discarded its other winning tile (suji), or if all four of one of its tiles have been seen (kabe)
+    let above_is_safe = value + 3 > 9 || discarded(value + 3) || walled(value + 1) || walled(value + 2);
+    let below_is_safe = value < 4 || discarded(value - 3) || walled(value - 1) || walled(value - 2);
+
+    let mut danger = match (above_is_safe, below_is_safe) {
+        // only kanchan, penchan, tanki and shanpon waits are left
+        (true, true) => if tile.is_terminal() { 2 } else { 3 },
+        (true, false) | (false, true) if (4..=6).contains(&value) => 6,
+        (false, false) => 8,
+        _ => 7,
+    };
+
+    // a shanpon needs two unseen copies
+    if num_unseen <= 1
+    {   danger -= 1;   }
+
+    // a player who threw a tile early usually isn't building a wait right next to it
+    let early_discard_nearby = opponent.discard_pile.iter().take(NUM_EARLY_DISCARDS)
+        .any(|discard| discard.suit == tile.suit && (discard.value as usize).abs_diff(value) <= 2);
+    if early_discard_nearby
+    {   danger -= 1;   }
+
+    // only genbutsu is completely safe
+    danger.max(1)
+}
+
+
+#[test]
+fn test_tile_danger()
+{
+    let no_tiles_seen = [0; NUM_TILE_KINDS];
+    let east = Tile { suit : Suit::Honor, value : SuitVal::East, red : false };
+
+    let mut opponent = Player::default();
+    opponent.discard_pile = vec![Tile::sou_tile(9), Tile::sou_tile(8), east, Tile::man_tile(4)];
+    opponent.tiles_others_called = vec![Tile::pin_tile(2)];
+
+    // genbutsu, including a discard someone called
+    assert_eq!(tile_danger(east, &opponent, &no_tiles_seen), 0);
+    assert_eq!(tile_danger(Tile::pin_tile(2), &opponent, &no_tiles_seen), 0);
+
+    // honors get safer as more of them are seen
+    let south = Tile { suit : Suit::Honor, value : SuitVal::South, red : false };
+    let mut seen_counts = no_tiles_seen;
+    assert_eq!(tile_danger(south, &opponent, &seen_counts), 5);
+    seen_counts[south.kind_idx()] = 3;
+    assert_eq!(tile_danger(south, &opponent, &seen_counts), 1);
+
+    // 4m makes 1m and 7m suji. The 7m can still be a kanchan or penchan, and the 6p can be anything
+    assert!(tile_danger(Tile::man_tile(1), &opponent, &no_tiles_seen) < tile_danger(Tile::man_tile(7), &opponent, &no_tiles_seen));
+    assert!(tile_danger(Tile::man_tile(7), &opponent, &no_tiles_seen) < tile_danger(Tile::pin_tile(6), &opponent, &no_tiles_seen));
+    assert_eq!(tile_danger(Tile::pin_tile(6), &opponent, &no_tiles_seen), 8);
+
+    // with every 7p seen, no ryanmen can wait on 6p from above, or 8p from below
+    let mut seen_counts = no_tiles_seen;
+    seen_counts[Tile::pin_tile(7).kind_idx()] = 4;
+    assert_eq!(tile_danger(Tile::pin_tile(6), &opponent, &seen_counts), 6);
+    assert_eq!(tile_danger(Tile::pin_tile(8), &opponent, &seen_counts), 3);
+
+    // early discards of 9s and 8s make the 7s a little safer than the 7p
+    assert!(tile_danger(Tile::sou_tile(7), &opponent, &no_tiles_seen) < tile_danger(Tile::pin_tile(7), &opponent, &no_tiles_seen));
+}
@@ -3,9 +3,10 @@ use std::io::Repeat;
 
 //pub mod game_structs {
 use strum::IntoEnumIterator;
-use rand::{Rng, rngs::adapter::ReseedingRng};
+use rand::{Rng, SeedableRng, rngs::StdRng, rngs::adapter::ReseedingRng};
 use unicode_segmentation::UnicodeSegmentation;
 use num::{pow, bigint::ParseBigIntError, One};
+use std::collections::HashMap;
 
 pub enum OutputView {
     BoardView,
@@ -34,22 +35,81 @@ use utils::*;
 pub mod command;
 use command::*;
 
+pub mod rules;
+use rules::*;
+
+pub mod tenhou_wall;
+use tenhou_wall::*;
+
+pub mod shanten;
+use shanten::*;
+
+pub mod defense;
+use defense::*;
+
+pub mod strategy;
+use strategy::*;
+
+pub mod monte_carlo;
+use monte_carlo::*;
+
 // TODO: TESTCASE: m2,m3,m4,p3,p4,p5,p8,s4,s4,s4,s6,s8,s8,s8 - should have four triplets, but no pairs
 
 
+#[derive(Clone, Copy, Eq, PartialEq)]
 enum RepeatHand {
         DealerWon,
-        RotateWinds
+        /// a non-dealer won, so the dealer seat moves on
+        RotateWinds,
+        /// exhaustive draw with the dealer in tenpai. The dealer keeps their seat
+        DealerTenpai,
+        /// exhaustive draw without the dealer in tenpai. The dealer seat moves on
+        DealerNoten,
+        /// the hand ended without a winner or an exhaustive draw, and is played again
+        AbortiveDraw,
     }
 
 
 pub enum NextPlayerOrWin {
     NextPlayer(usize),
-    Winner(usize)
+    /// the players who won by ron, in turn order from the player who discarded
+    Winners(Vec<usize>),
+    /// the hand ended early without a winner
+    AbortiveDraw(AbortiveDrawType),
+}
+
+/// ways a hand can end early without a winner (tochuu ryuukyoku)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AbortiveDrawType {
+    /// a player declared nine different terminals and honors on their first draw
+    KyuushuKyuuhai,
+    /// all four players discarded the same wind on their first turn
+    SuufonRenda,
+    /// all four players declared riichi
+    SuuchaRiichi,
+    /// four kans were declared by more than one player
+    Suukaikan,
+    /// three players called ron on the same tile
+    Sanchahou,
+}
+
+/// how a player's turn ended after they drew a tile
+pub enum TurnResult {
+    Discard(usize),
+    Tsumo,
+    /// other players called ron on the tile the current player added to a kan. Contains who won, or an abortive draw
+    Chankan(NextPlayerOrWin),
+    KyuushuKyuuhai,
 }
 
 
 const NUM_GAME_TILES : usize = 136;
+const DEAD_WALL_SIZE : usize = 14;
+/// replacement tiles drawn after a kan. These are the first tiles of the dead wall
+const NUM_REPLACEMENT_TILES : usize = 4;
+const MAX_DORA_INDICATORS : usize = 5;
+/// points a player puts on the table when declaring riichi
+const RIICHI_DEPOSIT : i32 = 1000;
 
 pub struct GameTiles {
     tiles : [Tile; NUM_GAME_TILES],
@@ -102,23 +162,55 @@ pub struct Game {
 //    data    : GameData,
 //    players : GamePlayers,
 
-    tiles : [Tile; NUM_GAME_TILES],
+    tiles : Vec<Tile>,
     pub next_tile : usize,
 
-    dora_idx : usize,
-    ura_dora_idx : usize,
+    /// index of the first tile of the dead wall. Every tile before it is part of the live wall
+    dead_wall_idx : usize,
+    /// number of dora indicators flipped over. Starts at 1 and goes up by 1 for every kan
+    num_dora_indicators : usize,
+    /// number of kans declared this hand. Each one draws a replacement tile from the dead wall
+    num_kans : usize,
+    /// number of replacement tiles drawn from the dead wall this hand, for kans and nuki dora
+    num_replacement_draws : usize,
+    /// true from when the current player draws a replacement tile until they discard. Winning on this tile is rinshan kaihou
+    drew_from_dead_wall : bool,
+    /// true when a player won by calling ron on a tile added to a kan (chankan)
+    kan_was_robbed : bool,
+
+    /// number of riichi deposits on the table. They're carried between hands until someone wins
+    pub riichi_sticks : usize,
+    /// repeat counter. Goes up by one whenever the dealer keeps their seat or a hand is drawn, and is reset when a non-dealer wins
+    pub honba : usize,
+    /// true when the current player's discard declares riichi. The deposit is only taken if no one rons the discard
+    riichi_deposit_pending : bool,
 
     curr_player_idx : usize,
-    players : [Player; NUM_PLAYERS],
+    players : Vec<Player>,
 
     pub human_is_playing : bool,
 
     pub num_called_tiles : usize,
 
     round_wind : SuitVal,
+    /// number of times the dealer seat has moved on this round
+    hand_in_round : usize,
+    /// the dealer of the first hand. Placement ties go to whoever sat closest to them
+    first_dealer_idx : usize,
     player_just_called : bool,
 
-
+    pub rules : RuleSet,
+
+    /// seed of the random number generator. The same seed, rules and player choices replay the same game
+    pub seed : u64,
+    /// every shuffle and AI random choice is drawn from here
+    rng : StdRng,
+    /// when set, walls are built with Tenhou's algorithm instead of being shuffled with rng
+    tenhou_wall : Option<TenhouWall>,
+    /// when set, the next hand is played with this wall instead of a shuffled one
+    scripted_wall : Option<Vec<Tile>>,
+    /// strategies set for computer players, by player index. Players without one use their ai_algorithm
+    ai_strategies : HashMap<usize, Box<dyn AIStrategy>>,
 }
 
 impl Default for Game {
@@ -128,12 +220,20 @@ impl Default for Game {
                 human_is_playing : false,
                 player_just_called : false,
                 round_wind : SuitVal::East,
+                hand_in_round : 0,
+                first_dealer_idx : 0,
                 num_called_tiles : 0,
+                rules : RuleSet::default(),
+                seed : 0,
+                rng : StdRng::seed_from_u64(0),
+                tenhou_wall : None,
+                scripted_wall : None,
+                ai_strategies : HashMap::new(),
 //            },
 
 //            players : GamePlayers {
                 curr_player_idx : usize::MAX,
-                players : [
+                players : vec![
                     Player::default().set_seat_wind(SuitVal::East).set_number(0).set_is_human().to_owned(),
                     Player::default().set_seat_wind(SuitVal::South).set_number(1).to_owned(),
                     Player::default().set_seat_wind(SuitVal::West).set_number(2).to_owned(),
@@ -143,10 +243,17 @@ impl Default for Game {
 
 //            walls : GameTiles {
 
-            dora_idx : NUM_GAME_TILES - 14,
-            ura_dora_idx : NUM_GAME_TILES - 7,
+            dead_wall_idx : NUM_GAME_TILES - DEAD_WALL_SIZE,
+            num_dora_indicators : 1,
+            num_kans : 0,
+            num_replacement_draws : 0,
+            drew_from_dead_wall : false,
+            kan_was_robbed : false,
+            riichi_sticks : 0,
+            honba : 0,
+            riichi_deposit_pending : false,
 
-            tiles : [
+            tiles : vec![
                 Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(1),
                 Tile::man_tile(2), Tile::man_tile(2), Tile::man_tile(2), Tile::man_tile(2),
                 Tile::man_tile(3), Tile::man_tile(3), Tile::man_tile(3), Tile::man_tile(3),
@@ -224,6 +331,150 @@ impl Default for Game {
 }
 
 impl Game {
+    /// a game played with the given rules and a random seed. Every player starts with the rule set's starting points
+    pub fn new(rules : RuleSet) -> Self
+    {
+        Game::with_seed(rules, rand::thread_rng().gen())
+    }
+
+    /// a game played with the given rules, which shuffles and plays the AI the same way every time it's given the same seed
+    pub fn with_seed(rules : RuleSet, seed : u64) -> Self
+    {
+        let mut game = Game { rules, seed, rng : StdRng::seed_from_u64(seed), ..Game::default() };
+
+        // sanma is played without the north seat or the 2 to 8 man tiles
+        if rules.sanma.is_some()
+        {
+            game.players.truncate(NUM_SANMA_PLAYERS);
+            game.tiles.retain(|tile| tile.suit != Suit::Man || tile.is_terminal());
+            game.dead_wall_idx = game.replacement_tiles_idx();
+        }
+
+        for player in &mut game.players
+        {
+            player.points = rules.starting_points;
+        }
+
+        game
+    }
+
+    /// a game whose walls are the ones Tenhou deals from the seed in a Tenhou log. None if the seed isn't valid, or for sanma
+    pub fn with_tenhou_seed(rules : RuleSet, seed : &str) -> Option<Self>
+    {
+        if rules.sanma.is_some()
+        {   return None;   }
+
+        let mut game = Game::new(rules);
+        game.tenhou_wall = Some(TenhouWall::from_seed(seed)?);
+
+        Some(game)
+    }
+
+    /// a game whose first hand is played with the wall in exactly this order. The wall is dealt one tile at a time starting from the first player,
+    /// the rest of the live wall is drawn in order, and the last 14 tiles are the dead wall. Later hands are shuffled
+    pub fn with_wall(rules : RuleSet, wall : Vec<Tile>) -> Self
+    {
+        let mut game = Game::with_seed(rules, 0);
+        assert_eq!(wall.len(), game.tiles.len(), "A scripted wall must have every tile in the game");
+
+        game.scripted_wall = Some(wall);
+        game
+    }
+
+    /// a game whose first hand starts with these hands, indexed by player, and where these tiles are drawn from the live wall in order.
+    /// Every other tile fills the rest of the wall in the usual order
+    pub fn with_hands_and_draws(rules : RuleSet, hands : Vec<Vec<Tile>>, draws : Vec<Tile>) -> Self
+    {
+        let mut game = Game::with_seed(rules, 0);
+        let num_players = game.num_players();
+        assert_eq!(hands.len(), num_players, "A starting hand is needed for every player");
+        assert!(hands.iter().all(|hand| hand.len() == PLAYER_HAND_SIZE - 1), "Every starting hand must have 13 tiles");
+
+        game.set_red_fives();
+        let mut remaining_tiles = game.tiles.clone();
+        let mut wall = vec![];
+
+        let dealt_tiles = (0..(PLAYER_HAND_SIZE - 1) * num_players).map(|i| hands[i % num_players][i / num_players]);
+        for tile in dealt_tiles.chain(draws)
+        {
+            // a five matches a red five if there aren't any plain fives left
+            let tile_idx = remaining_tiles.iter().position(|remaining_tile| *remaining_tile == tile)
+                .or_else(|| remaining_tiles.iter().position(|remaining_tile| remaining_tile.suit == tile.suit && remaining_tile.value == tile.value))
+                .unwrap_or_else(|| panic!("The scripted hands and draws have more of {} than the game does", tile));
+
+            wall.push(remaining_tiles.remove(tile_idx));
+        }
+
+        wall.append(&mut remaining_tiles);
+        game.scripted_wall = Some(wall);
+        game
+    }
+
+    /// makes the player a computer player, who decides everything with the given strategy
+    pub fn set_player_strategy(&mut self, player_idx : usize, strategy : Box<dyn AIStrategy>)
+    {
+        self.players[player_idx].is_human = false;
+        self.ai_strategies.insert(player_idx, strategy);
+    }
+
+    /// Asks a computer player's strategy for a decision, looking at the game from their seat.
+    /// The strategy looks at the game while choosing, so it borrows a copy of the rng and hands it back
+    fn ai_decide<T>(&mut self, player_idx : usize, decide : impl FnOnce(&mut dyn AIStrategy, &SeatView, &mut StdRng) -> T) -> T
+    {
+        let mut set_strategy = self.ai_strategies.remove(&player_idx);
+        let mut ai_algorithm = self.players[player_idx].ai_algorithm.clone();
+        let strategy : &mut dyn AIStrategy = match &mut set_strategy {
+            Some(set_strategy) => set_strategy.as_mut(),
+            None => &mut ai_algorithm,
+        };
+
+        let mut rng = self.rng.clone();
+        let decision = decide(strategy, &SeatView::new(self, player_idx), &mut rng);
+        self.rng = rng;
+
+        if let Some(set_strategy) = set_strategy
+        {
+            self.ai_strategies.insert(player_idx, set_strategy);
+        }
+
+        decision
+    }
+
+    /// one of possible_calls, or None, as chosen by a computer player
+    fn ai_choose_call(&mut self, player_idx : usize, tile : Tile, possible_calls : &[CalledSet]) -> Option<CalledSet>
+    {
+        if possible_calls.is_empty()
+        {   return None;   }
+
+        let call_made = self.ai_decide(player_idx, |strategy, view, rng| strategy.choose_call(view, tile, possible_calls, rng));
+        if let Some(call) = &call_made
+        {   assert!(possible_calls.contains(call), "An AI strategy chose a call it couldn't make");   }
+
+        call_made
+    }
+
+    pub fn num_players(&self) -> usize
+    {
+        self.players.len()
+    }
+
+    pub fn is_sanma(&self) -> bool
+    {
+        self.rules.sanma.is_some()
+    }
+
+    /// the first tile of the dead wall when no kans have been declared. Replacement tiles are drawn from here
+    fn replacement_tiles_idx(&self) -> usize
+    {
+        self.tiles.len() - DEAD_WALL_SIZE
+    }
+
+    /// dora indicators and ura dora indicators alternate at the end of the dead wall. Dora indicators take the even positions
+    fn dora_indicators_idx(&self) -> usize
+    {
+        self.tiles.len() - (MAX_DORA_INDICATORS * 2)
+    }
+
     fn current_player(&mut self) -> &mut Player
     {
         &mut self.players[self.curr_player_idx]
@@ -232,18 +483,46 @@ impl Game {
 
     fn draw_next_tile(&mut self) -> Option<Tile>
     {
-        if self.next_tile >= self.dora_idx
+        if self.live_wall_is_empty()
         {
             return None;
         }
         else
         {
+            self.drew_from_dead_wall = false;
             self.next_tile += 1;
 
             return Some(self.tiles[self.next_tile - 1]);
         }
     }
 
+    /// true once the last tile that can be drawn from the live wall has been drawn
+    fn live_wall_is_empty(&self) -> bool
+    {
+        self.next_tile >= self.dead_wall_idx
+    }
+
+    /// the face up dora indicators. The tile after each indicator is a dora
+    pub fn dora_indicators(&self) -> Vec<Tile>
+    {
+        (0..self.num_dora_indicators).map(|i| self.tiles[self.dora_indicators_idx() + (i * 2)]).collect()
+    }
+
+    /// the tiles under each face up dora indicator. Only revealed to a player who won after declaring riichi
+    pub fn ura_dora_indicators(&self) -> Vec<Tile>
+    {
+        (0..self.num_dora_indicators).map(|i| self.tiles[self.dora_indicators_idx() + (i * 2) + 1]).collect()
+    }
+
+    /// the tile after an indicator is dora. In sanma the 1 man indicates the 9 man, since the tiles between them aren't played
+    pub fn dora_from_indicator(&self, indicator : Tile) -> Tile
+    {
+        if self.is_sanma() && indicator == Tile::man_tile(1)
+        {   Tile::man_tile(9)   }
+        else
+        {   indicator.indicated_dora()   }
+    }
+
 
     /// returns the position of the human player within the player_list
     fn human_player_position(&self) -> usize
@@ -251,12 +530,84 @@ impl Game {
         0
     }
 
+    /// a kan can't be declared on the last tile of the live wall, or once every replacement tile has been drawn
+    fn can_declare_kan(&self) -> bool
+    {
+        self.num_kans < NUM_REPLACEMENT_TILES && ! self.live_wall_is_empty()
+    }
+
+    /// draws a replacement tile after a kan or nuki dora. The last tile of the live wall is moved into the dead wall
+    /// so that the dead wall stays at 14 tiles, which means the hand ends one draw sooner.
+    /// Once the four replacement tiles are used up (only possible with nuki dora), the tile moved into the dead wall is drawn instead
     fn draw_from_dead_wall(&mut self) -> Tile
     {
-        unimplemented!()
+        assert!(! self.live_wall_is_empty(), "A replacement tile can't be drawn once the live wall is empty");
+
+        self.dead_wall_idx -= 1;
+        let replacement_tile = if self.num_replacement_draws < NUM_REPLACEMENT_TILES
+        {   self.tiles[self.replacement_tiles_idx() + self.num_replacement_draws]   }
+        else
+        {   self.tiles[self.dead_wall_idx]   };
+
+        self.num_replacement_draws += 1;
+        self.drew_from_dead_wall = true;
+
+        replacement_tile
+    }
+
+    /// gives the player their replacement tile after a kan or nuki dora
+    fn draw_replacement_tile(&mut self, player_idx : usize)
+    {
+        let replacement_tile = self.draw_from_dead_wall();
+        self.players[player_idx].hand.push(replacement_tile);
+        self.players[player_idx].last_picked_tile = replacement_tile;
+    }
+
+    /// reveals a kan dora, then gives the player who called the kan their replacement tile
+    fn draw_kan_replacement_tile(&mut self, player_idx : usize)
+    {
+        self.break_ippatsu();
+        self.reveal_dora();
+
+        self.num_kans += 1;
+        self.draw_replacement_tile(player_idx);
+    }
+
+    /// in sanma a player can set a north aside as a dora on their turn, and draw a replacement tile.
+    /// A player in riichi can only set aside a north they just drew
+    fn player_can_nuki_dora(&self, player_idx : usize) -> bool
+    {
+        let player = &self.players[player_idx];
+
+        self.is_sanma()
+        && ! self.live_wall_is_empty()
+        && if player.is_in_riichi() { player.hand.last() == Some(&NORTH_TILE) } else { player.hand.contains(&NORTH_TILE) }
+    }
+
+    fn declare_nuki_dora(&mut self, player_idx : usize) -> TurnResult
+    {
+        // the drawn tile is on the end of the hand, so it's set aside before any north already in the hand
+        let north_idx = self.players[player_idx].hand.iter().rposition(|tile| *tile == NORTH_TILE)
+            .expect("Nuki dora declared without a north in hand");
+        self.players[player_idx].hand.remove(north_idx);
+        self.players[player_idx].nuki_dora += 1;
+
+        self.break_ippatsu();
+        self.draw_replacement_tile(player_idx);
+
+        self.player_choose_discard_idx_or_win(player_idx)
+    }
+
+    /// true if the player has a tile they could declare a closed or added kan with.
+    /// A player in riichi can't change their hand, so they can't kan
+    fn player_can_kan(&self, player_idx : usize) -> bool
+    {
+        self.can_declare_kan()
+        && ! self.players[player_idx].is_in_riichi()
+        && ( ! self.players[player_idx].closed_kan_tiles().is_empty() || ! self.players[player_idx].added_kan_tiles().is_empty() )
     }
 
-    fn open_closed_kan(&mut self, player_idx : usize, kanned_tile : Tile) -> Option<usize>
+    fn open_closed_kan(&mut self, player_idx : usize, kanned_tile : Tile) -> TurnResult
     {
         // add kan to revealed sets
         self.players[player_idx].hand.retain(|hand_tile| *hand_tile != kanned_tile);
@@ -267,37 +618,242 @@ impl Game {
             }
         );
 
-        let next_tile = self.draw_from_dead_wall();
-        self.players[player_idx].hand.push( next_tile );
+        // it's illegal to kan on the last tile, so there's always a replacement tile to draw or we've broken the rules
+        self.draw_kan_replacement_tile(player_idx);
+
+        self.choose_discard_after_kan(player_idx)
+    }
+
+    /// upgrades one of the player's pons to a kan with a tile from their hand.
+    /// Other players get a chance to rob the kan by calling ron on the added tile (chankan)
+    fn open_added_kan(&mut self, player_idx : usize, kanned_tile : Tile) -> TurnResult
+    {
+        let added_kan = CalledSet {
+            call_type : CallTypes::AddedKan,
+            set : Set::kan(kanned_tile),
+        };
+
+        // players deciding whether to ron are scoring a robbed kan
+        self.kan_was_robbed = true;
+        let mut calls_made = self.get_chankan_calls(player_idx, kanned_tile);
+        self.kan_was_robbed = false;
+        calls_made.push((player_idx, added_kan.clone()));
+
+        let highest_call_precedence = calls_made.iter().map(|call| call.1.call_type.precedence()).max().unwrap();
+        calls_made.retain(|call|
+            call.1.call_type.precedence() == highest_call_precedence
+        );
+
+        // ron beats the kan, so the kan is never completed
+        if let CallTypes::Ron(_) = calls_made[0].1.call_type
+        {
+            let robbed_tile_idx = self.players[player_idx].hand.iter().position(|hand_tile| *hand_tile == kanned_tile)
+                .expect("Tile added to kan was not in the player's hand");
+            self.players[player_idx].hand.remove(robbed_tile_idx);
+            self.kan_was_robbed = true;
+
+            return TurnResult::Chankan(self.resolve_rons(calls_made, kanned_tile));
+        }
+
+        self.players[player_idx].open_tiles_with_call(kanned_tile, added_kan);
+        self.draw_kan_replacement_tile(player_idx);
+
+        self.choose_discard_after_kan(player_idx)
+    }
+
+    /// players who can and want to rob an added kan with ron, in turn order from the player who declared it
+    fn get_chankan_calls(&mut self, kan_player_idx : usize, kanned_tile : Tile) -> Vec<(usize, CalledSet)>
+    {
+        let mut calls_made : Vec<(usize, CalledSet)> = vec![];
+
+        for i in 1..self.num_players()
+        {
+            let player_idx = (kan_player_idx + i) % self.num_players();
+            let player = &self.players[player_idx];
+
+            let can_ron = ! player.furiten && player.callable_tiles.get(&kanned_tile).is_some_and(|calls| calls.ron);
+            if can_ron
+            {
+                if let Some(call_made) = Player::choose_whether_to_rob_kan(player_idx, kanned_tile, self)
+                {
+                    calls_made.push((player_idx, call_made));
+                }
+            }
+        }
+
+        calls_made
+    }
+
+    /// after drawing a replacement tile the player can win, discard, or declare another kan
+    fn choose_discard_after_kan(&mut self, player_idx : usize) -> TurnResult
+    {
+        // a computer player's turn goes on as usual from the replacement tile
+        if ! self.players[player_idx].is_human
+        {
+            return self.player_choose_discard_idx_or_win(player_idx);
+        }
 
         let player_can_win = self.players[player_idx].check_complete_hand_and_update_waits();
-        // TODO: Rinshan Kaihou
+        let player_can_kan = self.player_can_kan(player_idx);
+        let player_can_riichi = self.can_declare_riichi(player_idx);
+        let player_can_nuki_dora = self.player_can_nuki_dora(player_idx);
 
-        // draw next tile. It's illegal to kan on the last tile, so there's always a tile to draw or we've broken the rules
-        // TODO: last tile from the wall is added to dead wall here
-        let discard_or_win = tui_output::get_player_discard_idx(self, player_idx, player_can_win, false);
+        let discard_or_win = tui_output::get_player_discard_idx(self, player_idx, player_can_win, player_can_kan, player_can_riichi, player_can_nuki_dora);
 
         match discard_or_win {
+            DiscardChoices::Win => {
+                self.players[player_idx].ron_or_tsumo = WinningMethod::Tsumo;
+                TurnResult::Tsumo
+            },
+            DiscardChoices::DiscardTile(tile_idx) => TurnResult::Discard(tile_idx),
+            DiscardChoices::Riichi(tile_idx) => {
+                self.declare_riichi(player_idx);
+                TurnResult::Discard(tile_idx)
+            },
+            DiscardChoices::OpenClosedKan(newly_kanned_tile) => self.open_closed_kan(player_idx, newly_kanned_tile),
+            DiscardChoices::AddedKan(newly_kanned_tile) => self.open_added_kan(player_idx, newly_kanned_tile),
+            DiscardChoices::NukiDora => self.declare_nuki_dora(player_idx),
+            // a kan is a call, so kyuushu kyuuhai can't be declared after one
+            DiscardChoices::KyuushuKyuuhai => unreachable!("Kyuushu kyuuhai can't be declared after a kan"),
+        }
+    }
+
+    /// the dealer is the player with the east seat wind
+    fn dealer_idx(&self) -> usize
+    {
+        self.players.iter()
+            .position(|player| player.seat_wind == SuitVal::East)
+            .expect("There was no player with East Wind who could be the dealer")
+    }
+
+    fn tiles_left_in_live_wall(&self) -> usize
+    {
+        self.dead_wall_idx.saturating_sub(self.next_tile)
+    }
+
+    /// true during a player's first turn, as long as no one has made a call (including closed kans) this hand
+    fn is_first_uninterrupted_turn(&self, player_idx : usize) -> bool
+    {
+        self.players[player_idx].discard_pile.is_empty()
+        && self.players[player_idx].tiles_others_called.is_empty()
+        && self.players.iter().all(|player| player.called_sets.is_empty())
+    }
 
-            DiscardChoices::Win => return None,
-            DiscardChoices::DiscardTile(tile_idx) => return Some(tile_idx),
-            DiscardChoices::OpenClosedKan(newly_kanned_tile) => return self.open_closed_kan(player_idx, newly_kanned_tile),
-            DiscardChoices::AddedKan(sdlkfj) => unimplemented!()
+    /// kyuushu kyuuhai can only be declared on the player's first uninterrupted draw
+    fn can_declare_kyuushu_kyuuhai(&self, player_idx : usize) -> bool
+    {
+        const KYUUSHU_KYUUHAI_MIN_TILES : usize = 9;
+
+        self.rules.abortive_draws.kyuushu_kyuuhai
+        && self.is_first_uninterrupted_turn(player_idx)
+        && self.players[player_idx].num_unique_terminals_and_honors() >= KYUUSHU_KYUUHAI_MIN_TILES
+    }
+
+    /// checks for the abortive draws which happen once a discard has gone by without a ron.
+    /// Suufon renda and suucha riichi need all four players, so sanma never has them
+    fn abortive_draw_after_discard(&self) -> Option<AbortiveDrawType>
+    {
+        let is_four_player = self.num_players() == NUM_PLAYERS;
+
+        let first_discards : Vec<Tile> = self.players.iter()
+            .filter(|player| player.discard_pile.len() == 1 && player.tiles_others_called.is_empty())
+            .map(|player| player.discard_pile[0])
+            .collect();
+        let no_calls_made = self.players.iter().all(|player| player.called_sets.is_empty());
+
+        let is_suufon_renda = is_four_player
+            && no_calls_made
+            && first_discards.len() == NUM_PLAYERS
+            && first_discards[0].suit == Suit::Honor
+            && ! first_discards[0].is_dragon()
+            && first_discards.iter().all(|tile| *tile == first_discards[0]);
+
+        let num_players_with_kans = self.players.iter()
+            .filter(|player| player.called_sets.iter().any(|called_set| called_set.set.set_type == SetType::Kan))
+            .count();
+
+        if self.rules.abortive_draws.suufon_renda && is_suufon_renda
+        {
+            Some(AbortiveDrawType::SuufonRenda)
+        }
+        else if self.rules.abortive_draws.suucha_riichi && is_four_player && self.players.iter().all(|player| player.is_in_riichi())
+        {
+            Some(AbortiveDrawType::SuuchaRiichi)
+        }
+        // one player with all four kans can still go for suukantsu
+        else if self.rules.abortive_draws.suukaikan && self.num_kans == NUM_REPLACEMENT_TILES && num_players_with_kans > 1
+        {
+            Some(AbortiveDrawType::Suukaikan)
+        }
+        else
+        {
+            None
+        }
+    }
+
+    /// a player can declare riichi with a closed hand that would be in tenpai after discarding,
+    /// as long as they can pay the deposit and there's at least one more draw left for everyone
+    fn can_declare_riichi(&self, player_idx : usize) -> bool
+    {
+        let player = &self.players[player_idx];
+
+        ! player.is_in_riichi()
+        && player.hand_is_closed()
+        && player.points >= RIICHI_DEPOSIT
+        && self.tiles_left_in_live_wall() >= self.num_players()
+        && ! player.tenpai_discard_idxs().is_empty()
+    }
+
+    /// declares riichi with the player's next discard. Riichi on the player's first uninterrupted turn is double riichi
+    fn declare_riichi(&mut self, player_idx : usize)
+    {
+        if self.is_first_uninterrupted_turn(player_idx)
+        {
+            self.players[player_idx].double_riichi = true;
+        }
+        else
+        {
+            self.players[player_idx].riichi = true;
         }
 
+        self.riichi_deposit_pending = true;
+    }
+
+    /// called once the discard declaring riichi wasn't ron'd. The deposit goes on the table and ippatsu starts
+    fn take_riichi_deposit(&mut self)
+    {
+        if ! self.riichi_deposit_pending
+        {   return;   }
+
+        self.riichi_deposit_pending = false;
+        self.riichi_sticks += 1;
+
+        let player = self.current_player();
+        player.points -= RIICHI_DEPOSIT;
+        player.iipatsu = true;
+    }
+
+    /// any call, including kans, ends every player's chance at ippatsu
+    fn break_ippatsu(&mut self)
+    {
+        for player in &mut self.players
+        {
+            player.iipatsu = false;
+        }
     }
 
+    /// flips over the next kan dora indicator
     fn reveal_dora(&mut self) -> ()
     {
-        if self.dora_idx < NUM_GAME_TILES
+        if self.num_dora_indicators < MAX_DORA_INDICATORS
         {
-            self.dora_idx += 1;
+            self.num_dora_indicators += 1;
         }
     }
 
     fn dump_game_state(&self)
     {
-        for i in 0..NUM_PLAYERS
+        for i in 0..self.num_players()
         {
             println!("Player {}", i);
             println!("-------------------------");
@@ -310,9 +866,13 @@ impl Game {
     {
         let mut calls_made : Vec<(usize, CalledSet)> = Vec::with_capacity(4);
 
-        for i in 0..NUM_PLAYERS{
-            if self.players[i] != self.players[self.curr_player_idx] && self.players[i].furiten == false
+        // ask in turn order from the discarder, so that simultaneous rons are in turn order too
+        for offset in 1..self.num_players(){
+            let i = (self.curr_player_idx + offset) % self.num_players();
+            if ! self.players[i].furiten
             {
+                let player_in_riichi = self.players[i].is_in_riichi();
+
                 println!("Checking if player {} needs tile {}. Their callable_tiles len is {}", i, discarded_tile, self.players[i].callable_tiles.len());
                 if self.players[i].callable_tiles.contains_key(&discarded_tile)
                 {
@@ -321,18 +881,38 @@ impl Game {
                     let mut possible_calls = entry.clone();
 
                     // checks if we're directly to the right of whoever discarded without overflow
-                    // if we're not, then we can't chii
-                    if (i + NUM_PLAYERS - 1) % NUM_PLAYERS != (self.curr_player_idx)
+                    // if we're not, then we can't chii. There's no chii at all in sanma
+                    if (i + self.num_players() - 1) % self.num_players() != (self.curr_player_idx) || self.is_sanma()
                     {
                         possible_calls.chii = false;
                     }
 
+                    possible_calls.open_kan &= self.can_declare_kan();
+                    // added kans are only made with a tile the player drew, never with a discard
+                    possible_calls.added_kan = false;
+
+                    // a player in riichi can only call ron
+                    if player_in_riichi
+                    {
+                        possible_calls = Calls { ron : possible_calls.ron, ron_set : possible_calls.ron_set, ..Calls::default() };
+                    }
+
                     if possible_calls.any_field_true()
                     {
                         let call_made = Player::choose_whether_to_call(i, discarded_tile, self);
 
                         if let Some(call_made) = call_made {
-                            calls_made.push((i, call_made));
+                            // a kan can't be called once the replacement tiles have run out or on the last discard
+                            let call_is_allowed = match call_made.call_type {
+                                CallTypes::OpenKan => possible_calls.open_kan,
+                                CallTypes::Chii => possible_calls.chii,
+                                _ => true,
+                            };
+
+                            if call_is_allowed
+                            {
+                                calls_made.push((i, call_made));
+                            }
                         }
                     }
                 }
@@ -349,64 +929,89 @@ impl Game {
     {
         let mut calls_made : Vec<(usize, CalledSet)> = self.get_calls_on_discard(discarded_tile);
 
-        // multiple calls can be made simultaneously. Higher precedence gets to call, and multiple
-        // people can ron at the same time too
-        if calls_made.len() > 1
+        if calls_made.is_empty()
         {
-            self.player_just_called = true;
-            // remove the discarded tile from the discarder's pile
-            self.current_player().discard_pile.pop();
-
-            let highest_call_precedence = calls_made.iter().max_by_key(
-                |call| call.1.call_type.precedence()
-            ).unwrap().1.call_type.precedence();
-
-            calls_made.retain(|call|
-                call.1.call_type.precedence() == highest_call_precedence
-            );
+            self.take_riichi_deposit();
+            self.player_just_called = false;
 
-            // this is only possible if multiple people Ron at the same time
-            if calls_made.len() > 1
-            {
-                unimplemented!();
-            }
-            else
+            if let Some(abortive_draw) = self.abortive_draw_after_discard()
             {
-                let call = &calls_made[0];
-                self.players[call.0].open_tiles_with_call(discarded_tile, call.1.clone());
-                self.current_player().tiles_others_called.push(discarded_tile);
-                // switch to the player who made the call
-                return match call.1.call_type
-                {
-                    CallTypes::Ron(set) => {
-                        self.players[call.0].ron_or_tsumo = WinningMethod::Ron(self.curr_player_idx);
-                        NextPlayerOrWin::Winner(call.0)
-                    },
-                    _ => NextPlayerOrWin::NextPlayer(call.0)
-                }
+                return NextPlayerOrWin::AbortiveDraw(abortive_draw);
             }
+
+            return NextPlayerOrWin::NextPlayer((self.curr_player_idx + 1) % self.num_players());
         }
-        else if calls_made.len() == 1
+
+        self.player_just_called = true;
+        // remove the discarded tile from the discarder's pile
+        self.current_player().discard_pile.pop();
+        self.current_player().tiles_others_called.push(discarded_tile);
+
+        // multiple calls can be made simultaneously. Higher precedence gets to call, and multiple
+        // people can ron at the same time too
+        let highest_call_precedence = calls_made.iter().max_by_key(
+            |call| call.1.call_type.precedence()
+        ).unwrap().1.call_type.precedence();
+
+        calls_made.retain(|call|
+            call.1.call_type.precedence() == highest_call_precedence
+        );
+
+        // only rons can share the highest precedence
+        if let CallTypes::Ron(_) = calls_made[0].1.call_type
         {
-            self.player_just_called = true;
-             // remove the discarded tile from the discarder's pile
-            self.current_player().discard_pile.pop();
+            return self.resolve_rons(calls_made, discarded_tile);
+        }
 
-            let call = &calls_made[0];
-            self.players[call.0].open_tiles_with_call(discarded_tile, call.1.clone());
-            self.current_player().tiles_others_called.push(discarded_tile);
+        self.take_riichi_deposit();
+        if let Some(abortive_draw) = self.abortive_draw_after_discard()
+        {
+            return NextPlayerOrWin::AbortiveDraw(abortive_draw);
+        }
 
-            return match call.1.call_type
-            {
-                CallTypes::Ron(set) => NextPlayerOrWin::Winner(call.0),
-                _ => NextPlayerOrWin::NextPlayer(call.0)
-            }
+        self.break_ippatsu();
+
+        let call = &calls_made[0];
+        self.players[call.0].open_tiles_with_call(discarded_tile, call.1.clone());
+
+        // switch to the player who made the call
+        match call.1.call_type
+        {
+            CallTypes::OpenKan => {
+                self.draw_kan_replacement_tile(call.0);
+                NextPlayerOrWin::NextPlayer(call.0)
+            },
+            _ => NextPlayerOrWin::NextPlayer(call.0)
         }
-        else
+    }
+
+    /// applies the double and triple ron rules when players call ron on the same tile, and completes each winner's hand.
+    /// The rons must be in turn order from the player who discarded or added to a kan
+    fn resolve_rons(&mut self, mut rons : Vec<(usize, CalledSet)>, ron_tile : Tile) -> NextPlayerOrWin
+    {
+        let num_winners = match rons.len() {
+            1 => 1,
+            2 => match self.rules.double_ron {
+                DoubleRonRule::BothWin => 2,
+                DoubleRonRule::Atamahane => 1,
+            },
+            _ if self.rules.abortive_draws.sanchahou => return NextPlayerOrWin::AbortiveDraw(AbortiveDrawType::Sanchahou),
+            _ => match self.rules.triple_ron {
+                TripleRonRule::AllWin => rons.len(),
+                TripleRonRule::Atamahane => 1,
+            },
+        };
+        rons.truncate(num_winners);
+
+        for (winner_idx, ron_call) in &rons
         {
-            self.player_just_called = false;
-            return NextPlayerOrWin::NextPlayer((self.curr_player_idx + 1) % NUM_PLAYERS);
+            let winner = &mut self.players[*winner_idx];
+            winner.open_tiles_with_call(ron_tile, ron_call.clone());
+            winner.last_picked_tile = ron_tile;
+            winner.ron_or_tsumo = WinningMethod::Ron(self.curr_player_idx);
         }
+
+        NextPlayerOrWin::Winners(rons.iter().map(|ron| ron.0).collect())
     }
 
     fn player_discard_tile(&mut self, player_idx : usize, discard_idx : usize) -> Tile
@@ -416,6 +1021,10 @@ impl Game {
             println!("Player number {} discarded tile {}. Deck marker is {}", player_idx, discard_idx, self.next_tile);
         }
 
+        self.drew_from_dead_wall = false;
+        // ippatsu only lasts until the player's next discard
+        self.players[player_idx].iipatsu = false;
+
         let discarded_tile = self.players[player_idx].hand.remove(discard_idx);
         self.players[player_idx].discard_pile.push(discarded_tile);
         self.players[player_idx].sort_hand();
@@ -425,7 +1034,7 @@ impl Game {
         return discarded_tile;
     }
 
-    fn player_choose_discard_idx_or_win(&mut self, player_idx : usize) -> Option<usize>
+    fn player_choose_discard_idx_or_win(&mut self, player_idx : usize) -> TurnResult
     {
         let mut discard_idx : usize;
         let mut player = &mut self.players[player_idx];
@@ -439,48 +1048,136 @@ impl Game {
             let player_can_win : bool = player.check_complete_hand_and_update_waits();
             player.hand = player_current_hand; // checking for a complete hand requires it be sorted
                                                                 // but we want the newest drawn tile to be shown to the right for discarding purposes
+            let player_can_kan = self.player_can_kan(player_idx);
+            let player_can_riichi = self.can_declare_riichi(player_idx);
+            let player_can_nuki_dora = self.player_can_nuki_dora(player_idx);
 
             tui_output::output_game(self, player_idx);
-            let discard_choice = tui_output::get_player_discard_idx(self, player_idx, player_can_win, false);
+            if self.can_declare_kyuushu_kyuuhai(player_idx) && tui_output::get_player_kyuushu_kyuuhai_choice(self, player_idx)
+            {
+                return TurnResult::KyuushuKyuuhai;
+            }
+
+            let discard_choice = tui_output::get_player_discard_idx(self, player_idx, player_can_win, player_can_kan, player_can_riichi, player_can_nuki_dora);
             let player = &mut self.players[player_idx];
 
             match discard_choice {
                 DiscardChoices::DiscardTile(idx) => discard_idx = idx,
+                DiscardChoices::Riichi(idx) => {
+                    self.declare_riichi(player_idx);
+                    discard_idx = idx;
+                },
                 DiscardChoices::Win => {
                     player.ron_or_tsumo = WinningMethod::Tsumo;
-                    return None
+                    return TurnResult::Tsumo
                 },
-                DiscardChoices::OpenClosedKan(kanned_tile) => match self.open_closed_kan(player_idx, kanned_tile)
-                    {
-                        Some(idx) => discard_idx = idx,
-                        // TODO: Update winning method accordingly if needed
-                        None => return None
-                    },
-                DiscardChoices::AddedKan(kanned_tile) => unimplemented!(),
+                DiscardChoices::OpenClosedKan(kanned_tile) => return self.open_closed_kan(player_idx, kanned_tile),
+                DiscardChoices::AddedKan(kanned_tile) => return self.open_added_kan(player_idx, kanned_tile),
+                DiscardChoices::KyuushuKyuuhai => return TurnResult::KyuushuKyuuhai,
+                DiscardChoices::NukiDora => return self.declare_nuki_dora(player_idx),
             }
         }
         // computer picks which to discard
         else
         {
-            discard_idx = player.ai_discard();
-            tui_output::output_game(self, self.human_player_position());
-            let mut input = String::from("");
-            std::io::stdin().read_line(&mut input).expect("stdin readline failed");
-        }
-
-        return Some(discard_idx);
-    }
+            // checking for a complete hand requires it be sorted, but the drawn tile stays on the right for discarding
+            let player_current_hand = player.hand.clone();
+            player.sort_hand();
+            let player_can_win = player.check_complete_hand_and_update_waits();
+            player.hand = player_current_hand;
 
+            if player_can_win && self.ai_decide(player_idx, |strategy, view, _| strategy.declare_tsumo(view))
+            {
+                self.players[player_idx].ron_or_tsumo = WinningMethod::Tsumo;
+                return TurnResult::Tsumo;
+            }
 
-    
+            if self.can_declare_kyuushu_kyuuhai(player_idx) && self.ai_decide(player_idx, |strategy, view, _| strategy.declare_kyuushu_kyuuhai(view))
+            {
+                return TurnResult::KyuushuKyuuhai;
+            }
+
+            // a north is always worth more as nuki dora than in the hand
+            if self.player_can_nuki_dora(player_idx)
+            {
+                return self.declare_nuki_dora(player_idx);
+            }
+
+            if self.player_can_kan(player_idx)
+            {
+                let closed_kan_tiles = self.players[player_idx].closed_kan_tiles();
+                let kan_tiles = [closed_kan_tiles.clone(), self.players[player_idx].added_kan_tiles()].concat();
+
+                if let Some(kanned_tile) = self.ai_decide(player_idx, |strategy, view, _| strategy.declare_kan(view, &kan_tiles))
+                {
+                    assert!(kan_tiles.contains(&kanned_tile), "An AI strategy declared a kan it couldn't make");
+
+                    return if closed_kan_tiles.contains(&kanned_tile)
+                    {   self.open_closed_kan(player_idx, kanned_tile)   }
+                    else
+                    {   self.open_added_kan(player_idx, kanned_tile)    };
+                }
+            }
+
+            // a hand in riichi is locked, so the drawn tile is always discarded
+            if self.players[player_idx].is_in_riichi()
+            {
+                discard_idx = self.players[player_idx].hand.len() - 1;
+            }
+            else
+            {
+                discard_idx = self.ai_decide(player_idx, |strategy, view, rng| strategy.choose_discard(view, rng));
+                assert!(discard_idx < self.players[player_idx].hand.len(), "An AI strategy chose a discard outside of its hand");
+
+                if self.can_declare_riichi(player_idx)
+                {
+                    let tenpai_discard_idxs = self.players[player_idx].tenpai_discard_idxs();
+                    let riichi_discard_idx = self.ai_decide(player_idx, |strategy, view, _| strategy.declare_riichi(view, discard_idx, &tenpai_discard_idxs));
+
+                    if let Some(riichi_discard_idx) = riichi_discard_idx
+                    {
+                        assert!(tenpai_discard_idxs.contains(&riichi_discard_idx), "An AI strategy declared riichi with a discard that isn't tenpai");
+                        discard_idx = riichi_discard_idx;
+                        self.declare_riichi(player_idx);
+                    }
+                }
+            }
+
+            // let the human watch each computer turn
+            if self.human_is_playing
+            {
+                tui_output::output_game(self, self.human_player_position());
+                let mut input = String::from("");
+                std::io::stdin().read_line(&mut input).expect("stdin readline failed");
+            }
+        }
+
+        TurnResult::Discard(discard_idx)
+    }
+
+
+    
 
     // fisher yates shuffle of the game tiles
     fn shuffle(&mut self) -> ()
     {
         self.next_tile = 0;
 
-        for i in 0..NUM_GAME_TILES-2 {
-            let random_idx : usize = rand::thread_rng().gen_range(i..NUM_GAME_TILES);
+        if let Some(scripted_wall) = self.scripted_wall.take()
+        {
+            self.tiles = scripted_wall;
+            return;
+        }
+
+        if let Some(tenhou_wall) = &mut self.tenhou_wall
+        {
+            let (yama, _dice) = tenhou_wall.next_hand();
+            self.tiles = self.tiles_from_tenhou_yama(&yama);
+            return;
+        }
+
+        for i in 0..self.tiles.len()-2 {
+            let random_idx : usize = self.rng.gen_range(i..self.tiles.len());
 
             // exchange tiles from i and random index
             let mut temp : Tile = self.tiles[i];
@@ -489,21 +1186,133 @@ impl Game {
         }
     }
 
+    /// orders Tenhou's wall so every player is dealt and draws the same tiles they would on Tenhou.
+    /// Tenhou draws from yama[135] down to its dead wall at yama[0..14]
+    fn tiles_from_tenhou_yama(&self, yama : &[usize]) -> Vec<Tile>
+    {
+        let num_players = self.num_players();
+        let dealer_idx = self.dealer_idx();
+        let tenhou_tile = |yama_idx : usize| tile_from_tenhou_id(yama[yama_idx], self.rules.red_fives_per_suit);
+
+        let draws : Vec<Tile> = (0..NUM_GAME_TILES).rev().map(tenhou_tile).collect();
+
+        // starting with the dealer, Tenhou deals four tiles to each player three times, then one more each
+        let mut starting_hands = vec![vec![]; num_players];
+        let mut num_dealt = 0;
+        for num_tiles in [4, 4, 4, 1]
+        {
+            for hand in &mut starting_hands
+            {
+                hand.extend_from_slice(&draws[num_dealt..num_dealt + num_tiles]);
+                num_dealt += num_tiles;
+            }
+        }
+
+        // divy_tiles_to_players deals one tile at a time, starting with the first player
+        let mut tiles = vec![];
+        for i in 0..num_dealt
+        {
+            let player_idx = i % num_players;
+            tiles.push(starting_hands[(player_idx + num_players - dealer_idx) % num_players][i / num_players]);
+        }
+
+        tiles.extend_from_slice(&draws[num_dealt..NUM_GAME_TILES - DEAD_WALL_SIZE]);
+
+        // replacement tiles come from yama 1, 0, 3 then 2. Dora indicators start from yama[5] and ura dora indicators from yama[4]
+        tiles.extend([1, 0, 3, 2].map(tenhou_tile));
+        for i in 0..MAX_DORA_INDICATORS
+        {
+            tiles.push(tenhou_tile(5 + i * 2));
+            tiles.push(tenhou_tile(4 + i * 2));
+        }
+
+        tiles
+    }
+
+    /// how many of each tile kind every player can see: discards, called sets, dora indicators and nuki dora
+    pub fn visible_tile_counts(&self) -> [u8; NUM_TILE_KINDS]
+    {
+        let mut visible_tiles = self.dora_indicators();
+        for player in &self.players
+        {
+            visible_tiles.extend_from_slice(&player.discard_pile);
+            visible_tiles.extend(player.called_sets.iter().flat_map(|called_set| called_set.set.tiles.iter()));
+            visible_tiles.extend(std::iter::repeat_n(NORTH_TILE, player.nuki_dora));
+        }
+
+        tile_counts(&visible_tiles)
+    }
+
+    /// the ukeire of each discard the player could make, from the tiles they can see. Sorted from the best discard to the worst
+    pub fn player_discard_ukeire(&self, player_idx : usize) -> Vec<DiscardUkeire>
+    {
+        let player = &self.players[player_idx];
+        self.hand_discard_ukeire(&player.hand, player.called_sets.len())
+    }
+
+    /// the ukeire of each discard from a hand, from the tiles every player can see. Sorted from the best discard to the worst
+    pub fn hand_discard_ukeire(&self, hand : &[Tile], num_called_sets : usize) -> Vec<DiscardUkeire>
+    {
+        let mut discards = discard_ukeire(hand, num_called_sets, &self.visible_tile_counts());
+
+        // there's no 2 to 8 man to draw in sanma
+        if self.is_sanma()
+        {
+            for discard in &mut discards
+            {
+                discard.accepted_tiles.retain(|(tile, _)| tile.suit != Suit::Man || tile.is_terminal());
+            }
+        }
+
+        discards.sort_by_key(|discard| (discard.shanten, std::cmp::Reverse(discard.num_accepted())));
+        discards
+    }
+
+    /// sha512 of the wall in the order it's drawn, as hex. Publishing it before a hand lets players check afterwards that the wall wasn't changed
+    pub fn wall_hash(&self) -> String
+    {
+        let wall : String = self.tiles.iter().map(|tile| tile.to_string()).collect();
+        to_hex(&sha512(wall.as_bytes()))
+    }
+
+    /// marks the first fives of each suit red, up to the number of red fives in the rules
+    fn set_red_fives(&mut self)
+    {
+        let mut red_fives_left = [self.rules.red_fives_per_suit; 3];
+
+        for tile in &mut self.tiles
+        {
+            if tile.value != SuitVal::Five
+            {   continue;   }
+
+            let suit_idx = match tile.suit {
+                Suit::Man => 0,
+                Suit::Pin => 1,
+                Suit::Sou => 2,
+                Suit::Honor => continue,
+            };
+
+            tile.red = red_fives_left[suit_idx] > 0;
+            red_fives_left[suit_idx] = red_fives_left[suit_idx].saturating_sub(1);
+        }
+    }
+
     fn divy_tiles_to_players(&mut self) -> ()
     {   // players start with 13 tiles and draw their 14th each turn
         for player in &mut self.players{
             player.hand.clear();
         }
 
-        for i in 0..((PLAYER_HAND_SIZE - 1) * NUM_PLAYERS)
+        let num_players = self.num_players();
+        for i in 0..((PLAYER_HAND_SIZE - 1) * num_players)
         {
-            self.players[i % NUM_PLAYERS].hand.push(self.tiles[self.next_tile as usize]);
+            self.players[i % num_players].hand.push(self.tiles[self.next_tile]);
             self.next_tile += 1;
         }
 
-        for i in 0..NUM_PLAYERS
+        for player in &mut self.players
         {
-            self.players[i].sort_hand();
+            player.sort_hand();
         }
     }
 
@@ -511,9 +1320,20 @@ impl Game {
     /// divying them to players, clearing player discards, etc.
     fn setup_for_hand(&mut self) -> ()
     {
+        self.set_red_fives();
         self.shuffle();
         self.divy_tiles_to_players();
 
+        self.dead_wall_idx = self.replacement_tiles_idx();
+        self.num_dora_indicators = 1;
+        self.num_kans = 0;
+        self.num_replacement_draws = 0;
+        self.drew_from_dead_wall = false;
+        self.kan_was_robbed = false;
+        self.riichi_deposit_pending = false;
+        // the last hand may have ended on a ron, but the dealer still draws first
+        self.player_just_called = false;
+
         // clear discards
         for player in &mut self.players{
             player.discard_pile.clear();
@@ -522,6 +1342,14 @@ impl Game {
             player.winning_call_tiles.clear();
             player.callable_tiles.clear();
 
+            player.riichi = false;
+            player.double_riichi = false;
+            player.iipatsu = false;
+            player.nuki_dora = 0;
+            player.ron_or_tsumo = WinningMethod::NotWonYet;
+            player.furiten = false;
+            player.tenpai = false;
+
             player.update_callable_tiles();
         }
     }
@@ -530,10 +1358,7 @@ impl Game {
     {
         self.setup_for_hand();
 
-        // Dealer is the east wind player
-        self.curr_player_idx = self.players.iter()
-            .position(|player| player.seat_wind == SuitVal::East)
-            .expect("There was no player with East Wind who could be the dealer");
+        self.curr_player_idx = self.dealer_idx();
 
         loop
         {
@@ -544,26 +1369,37 @@ impl Game {
                     if next_tile.is_none()
                     {
                         scoring::score_points(self, None);
-                        return RepeatHand::RotateWinds;
+
+                        let dealer_idx = self.dealer_idx();
+                        if self.players[dealer_idx].tenpai
+                        {
+                            return RepeatHand::DealerTenpai;
+                        }
+                        else
+                        {
+                            return RepeatHand::DealerNoten;
+                        }
                     }
 
                     let next_tile = unsafe {next_tile.unwrap_unchecked()};
                     self.current_player().hand.push(next_tile);
+                    self.current_player().last_picked_tile = next_tile;
                 }
 
                 // push the next tile without sorting to keep the tile on the right for display purposes
                 // since after discarding
-                let discarded_idx = self.player_choose_discard_idx_or_win(self.curr_player_idx);
-                let mut discarded_tile : Tile;
-                if let Some(idx) = discarded_idx
-                {
-                    discarded_tile = self.player_discard_tile(self.curr_player_idx, idx);
-                }
-                else // None
+                let next_or_win = match self.player_choose_discard_idx_or_win(self.curr_player_idx)
                 {
+                    TurnResult::Discard(idx) => {
+                        let discarded_tile = self.player_discard_tile(self.curr_player_idx, idx);
+                        self.current_player().sort_hand();
+
+                        self.execute_call_or_advance_player(discarded_tile)
+                    },
                     // A player always discards, unless they chose to win
+                    TurnResult::Tsumo => {
                         scoring::score_points(self, Some(self.curr_player_idx));
-                        if self.players[self.curr_player_idx].seat_wind == self.round_wind
+                        if self.curr_player_idx == self.dealer_idx()
                         {
                             return RepeatHand::DealerWon;
                         }
@@ -571,21 +1407,19 @@ impl Game {
                         {
                             return RepeatHand::RotateWinds;
                         }
-                }
-
-                self.current_player().sort_hand();
-
-                let next_or_win = self.execute_call_or_advance_player(discarded_tile);
+                    },
+                    // robbing a kan is scored the same as a ron on a discard
+                    TurnResult::Chankan(next_or_win) => next_or_win,
+                    TurnResult::KyuushuKyuuhai => NextPlayerOrWin::AbortiveDraw(AbortiveDrawType::KyuushuKyuuhai),
+                };
 
                 match next_or_win {
                     NextPlayerOrWin::NextPlayer(next_index) => self.curr_player_idx = next_index,
-                    NextPlayerOrWin::Winner(winner_index) => {
-                        self.players[winner_index].ron_or_tsumo = WinningMethod::Ron(self.curr_player_idx);
-                        self.curr_player_idx = winner_index;
-                        scoring::score_points(self, Some(winner_index));
+                    NextPlayerOrWin::Winners(winner_idxs) => {
+                        // each winner is paid separately by the discarder. The honba and riichi sticks go to the first winner in turn order
+                        scoring::score_ron_winners(self, &winner_idxs);
 
-                        let temp_wind = self.round_wind;
-                        if self.current_player().seat_wind == temp_wind
+                        if winner_idxs.contains(&self.dealer_idx())
                         {
                             return RepeatHand::DealerWon;
                         }
@@ -593,43 +1427,158 @@ impl Game {
                         {
                             return RepeatHand::RotateWinds;
                         }
-                    }
+                    },
+                    // the riichi sticks stay on the table for the next hand
+                    NextPlayerOrWin::AbortiveDraw(abortive_draw) => {
+                        tui_output::output_abortive_draw(abortive_draw, self.human_is_playing);
+                        return RepeatHand::AbortiveDraw;
+                    },
                 }
         };
     }
 
-    fn play_round(&mut self) -> ()
+    /// plays hands until the dealer seat has passed to every player. Returns true if the game ended during the round
+    fn play_round(&mut self) -> bool
     {
-        const HANDS_PER_ROUND : usize = 4;
-
-        let mut times_rotated : usize = 0;
-        while times_rotated < HANDS_PER_ROUND
+        self.hand_in_round = 0;
+        while self.hand_in_round < self.num_players()
         {
 
             let rotate_or_stay = self.play_hand();
 
-            if let RepeatHand::RotateWinds = rotate_or_stay
+            // the dealer keeps their seat (renchan) after winning or being in tenpai at an exhaustive draw
+            let rotate_winds = match rotate_or_stay {
+                RepeatHand::RotateWinds => {
+                    self.honba = 0;
+                    true
+                },
+                RepeatHand::DealerNoten => {
+                    self.honba += 1;
+                    true
+                },
+                RepeatHand::DealerWon | RepeatHand::DealerTenpai | RepeatHand::AbortiveDraw => {
+                    self.honba += 1;
+                    false
+                },
+            };
+
+            if self.game_is_over(rotate_or_stay, rotate_winds)
+            {
+                return true;
+            }
+
+            if rotate_winds
             {
-                times_rotated += 1;
+                self.hand_in_round += 1;
 
+                let num_players = self.num_players();
                 for player in &mut self.players
                 {
-                    player.rotate_wind();
+                    player.rotate_wind(num_players);
                 }
             }
 
         }
 
+        false
+    }
+
+    /// 0 for the east round, 1 for south, and so on
+    fn round_idx(&self) -> usize
+    {
+        match self.round_wind {
+            SuitVal::East => 0,
+            SuitVal::South => 1,
+            SuitVal::West => 2,
+            SuitVal::North => 3,
+            _ => panic!("Error: {} is not a round wind", self.round_wind)
+        }
+    }
+
+    /// without a west extension the game can end whatever the scores are
+    fn someone_reached_target_points(&self, points : &[i32]) -> bool
+    {
+        ! self.rules.west_extension || points.iter().any(|points| *points >= self.rules.target_points)
+    }
+
+    /// checks whether the game ends after the hand that was just played
+    fn game_is_over(&self, hand_result : RepeatHand, rotate_winds : bool) -> bool
+    {
+        let points : Vec<i32> = self.players.iter().map(|player| player.points).collect();
+        self.game_is_over_with_points(&points, hand_result, rotate_winds)
+    }
+
+    /// checks whether the game would end after the hand being played, if it left the players with these points
+    fn game_is_over_with_points(&self, points : &[i32], hand_result : RepeatHand, rotate_winds : bool) -> bool
+    {
+        // bust
+        if self.rules.tobi && points.iter().any(|points| *points < 0)
+        {
+            return true;
+        }
+
+        let num_rounds = self.rules.game_length.num_rounds();
+        let is_last_hand_of_round = self.hand_in_round == self.num_players() - 1;
+
+        // the extension round ends as soon as someone reaches the target, or once it's been played through
+        if self.round_idx() >= num_rounds
+        {
+            return self.someone_reached_target_points(points) || (is_last_hand_of_round && rotate_winds);
+        }
+
+        // the last hand of the game (oorasu)
+        if self.round_idx() == num_rounds - 1 && is_last_hand_of_round
+        {
+            if rotate_winds
+            {
+                return self.someone_reached_target_points(points);
+            }
+
+            // the dealer can stop after winning while in first place (agari-yame)
+            return self.rules.agari_yame
+                && hand_result == RepeatHand::DealerWon
+                && self.placements_with_points(points)[0] == self.dealer_idx()
+                && self.someone_reached_target_points(points);
+        }
+
+        false
     }
 
+    /// player indexes from first place to last. Ties go to whoever sat closest to the first dealer in turn order
+    pub fn placements(&self) -> Vec<usize>
+    {
+        let points : Vec<i32> = self.players.iter().map(|player| player.points).collect();
+        self.placements_with_points(&points)
+    }
+
+    /// placements if each player had the points at their index instead
+    fn placements_with_points(&self, points : &[i32]) -> Vec<usize>
+    {
+        let num_players = self.num_players();
+        let mut placements : Vec<usize> = (0..num_players).collect();
 
-    pub fn play_game(&mut self, num_rounds : u8) -> ()
+        placements.sort_by_key(|player_idx| (
+            std::cmp::Reverse(points[*player_idx]),
+            (player_idx + num_players - self.first_dealer_idx) % num_players
+        ));
+
+        placements
+    }
+
+    pub fn play_game(&mut self)
     {
         self.round_wind = SuitVal::East;
+        self.first_dealer_idx = self.dealer_idx();
+
+        // the west extension is played when no one has reached the target points by the end of the game
+        let max_rounds = self.rules.game_length.num_rounds() + self.rules.west_extension as usize;
 
-        for i in 0..num_rounds
+        for i in 0..max_rounds
         {
-            self.play_round();
+            if self.play_round()
+            {
+                break;
+            }
 
             // round winds change counter clockwise while player seat winds change clockwise (Weird)
             self.round_wind = match self.round_wind {
@@ -640,8 +1589,6 @@ impl Game {
                 _ => panic!("Error: Attempted to advance to next round from a round wind value of {}", self.round_wind)
             }
         }
-
-
     }
 }
 
@@ -740,6 +1687,7 @@ fn test_kokushi_musou()
     game.players[0].ron_or_tsumo = WinningMethod::Tsumo;
     game.players[0].last_picked_tile = Tile { suit : Suit::Honor, value : SuitVal::East, red : false };
     game.next_tile = 1;
+    game.rules.double_yakuman = true;
 
     assert_eq!(scoring::yakuman_kokushi_musou(&game.players[0], &game), 2);
     assert_eq!(scoring::yakuman_chiihou(&game.players[0], &game), 1);
@@ -839,6 +1787,7 @@ fn test_suuankou()
     game.players[0].last_picked_tile = Tile::man_tile(7);
     game.players[0].ron_or_tsumo = WinningMethod::Ron(3);
     game.next_tile = 45;
+    game.rules.double_yakuman = true;
 
 
     assert_eq!(yakuman_suuankou(&game.players[0], &game), 2);
@@ -1081,93 +2030,1310 @@ fn test_fu_open_pinfu()
 }
 
 
+#[test]
+fn test_yaku_pinfu_tanyao_iipeikou()
+{
+    let mut game = Game::default();
+
+    let winning_player = &mut game.players[1];
+
+    winning_player.hand = vec!(
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::pin_tile(5), Tile::pin_tile(6), Tile::pin_tile(7),
+        Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+        Tile::sou_tile(5), Tile::sou_tile(5),
+    );
+    winning_player.sort_hand();
 
+    winning_player.last_picked_tile = Tile::sou_tile(8);
+    winning_player.ron_or_tsumo = WinningMethod::Tsumo;
 
+    let interpretations = game.players[1].winning_hand_interpretations();
+    assert_eq!(interpretations.len(), 1);
+
+    let hand = &interpretations[0];
+    assert_eq!(hand.wait, WaitType::Ryanmen);
+    assert_eq!(yaku_menzenchin_tsumohou(&game.players[1], &game, hand), 1);
+    assert_eq!(yaku_pinfu(&game.players[1], &game, hand), 1);
+    assert_eq!(yaku_iipeikou(&game.players[1], &game, hand), 1);
+    assert_eq!(yaku_tanyao(&game.players[1], &game, hand), 1);
+    assert_eq!(yaku_ryanpeikou(&game.players[1], &game, hand), 0);
+    assert_eq!(yaku_yakuhai(&game.players[1], &game, hand), 0);
+
+    // pinfu tsumo stays at 20 fu. 4 han 20 fu
+    assert_eq!(game.players[1].hand_fu(&game, true), 20);
+    assert_eq!(game.players[1].score_hand_basic_points(&game), 20 * 64);
+}
 
 #[test]
-fn test_callable_tiles()
+fn test_yaku_open_hand_reductions()
 {
-    {
-        let mut player = Player {
-            hand : vec![
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-            ],
-            ..Player::default()
-        };
+    let mut game = Game::default();
+    let dora_indicators_idx = game.dora_indicators_idx();
+    // the unshuffled wall's dora indicator would make the white dragons dora
+    game.tiles[dora_indicators_idx] = Tile::pin_tile(1);
 
-        player.sort_hand();
-        player.update_callable_tiles();
+    let winning_player = &mut game.players[1];
 
-        assert_eq!(player.callable_tiles.len(), 1);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::West, red : true} ), true);
+    winning_player.hand = vec!(
+        Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+    );
+    winning_player.sort_hand();
 
-        let mut player = Player {
-            hand : vec![
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-            ],
-            ..Player::default()
-        };
-    }
+    winning_player.called_sets = vec!(
+        CalledSet {
+            set : Set::sequence(Tile::man_tile(1)),
+            call_type : CallTypes::Chii
+        },
+        CalledSet {
+            set : Set::sequence(Tile::man_tile(7)),
+            call_type : CallTypes::Ron(SetType::Sequence)
+        },
+    );
 
-    {
-        let mut player = Player {
-            hand : vec![
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
-                Tile::man_tile(2),
-                Tile::man_tile(3),
-                Tile::man_tile(4),
-                Tile::man_tile(5),
-                Tile::man_tile(6),
-            ],
-            ..Player::default()
-        };
+    winning_player.last_picked_tile = Tile::man_tile(9);
+    winning_player.ron_or_tsumo = WinningMethod::Ron(2);
+
+    let interpretations = game.players[1].winning_hand_interpretations();
+    assert_eq!(interpretations.len(), 1);
+
+    let hand = &interpretations[0];
+    assert_eq!(hand.open_sets.len(), 2);
+    assert_eq!(yaku_ikkitsuukan(&game.players[1], &game, hand), 1);
+    assert_eq!(yaku_honitsu(&game.players[1], &game, hand), 2);
+    assert_eq!(yaku_chinitsu(&game.players[1], &game, hand), 0);
+    assert_eq!(yaku_yakuhai(&game.players[1], &game, hand), 1);
+    assert_eq!(yaku_pinfu(&game.players[1], &game, hand), 0);
+
+    // 20 base + 8 for the concealed white triplet + 2 for the round wind pair, rounded up to 30 fu at 4 han
+    assert_eq!(game.players[1].hand_fu(&game, true), 30);
+    assert_eq!(game.players[1].score_hand_basic_points(&game), 30 * 64);
+
+    // the same tiles with the chii made closed are worth one more han for each of ittsu and honitsu
+    game.players[1].called_sets[0].call_type = CallTypes::Ron(SetType::Sequence);
+    game.players[1].called_sets.remove(1);
+    game.players[1].hand.append(&mut vec![Tile::man_tile(7), Tile::man_tile(8), Tile::man_tile(9)]);
+    game.players[1].sort_hand();
+    game.players[1].last_picked_tile = Tile::man_tile(1);
+
+    let hand = &game.players[1].winning_hand_interpretations()[0];
+    assert_eq!(yaku_ikkitsuukan(&game.players[1], &game, hand), 2);
+    assert_eq!(yaku_honitsu(&game.players[1], &game, hand), 3);
+}
 
-        player.sort_hand();
-        player.update_callable_tiles();
+#[test]
+fn test_yaku_best_interpretation()
+{
+    let mut game = Game::default();
 
-        assert_eq!(player.callable_tiles.len(), 9);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::West, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::East, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::One, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Two, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Three, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Four, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Five, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Six, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Seven, red : true} ), true);
-    }
+    let winning_player = &mut game.players[2];
 
-    {
-        let mut player = Player {
-            hand : vec![
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
-                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
-                Tile::man_tile(2),
-                Tile::man_tile(3),
-                Tile::man_tile(4),
-                Tile::man_tile(5),
-                Tile::man_tile(6),
-            ],
-            tenpai : true,
-            ..Player::default()
-        };
+    winning_player.hand = vec!(
+        Tile::man_tile(2), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(4),
+        Tile::pin_tile(5), Tile::pin_tile(5), Tile::pin_tile(6), Tile::pin_tile(6), Tile::pin_tile(7), Tile::pin_tile(7),
+        Tile::sou_tile(9), Tile::sou_tile(9),
+    );
+    winning_player.sort_hand();
 
-        player.sort_hand();
-        player.update_callable_tiles();
+    winning_player.last_picked_tile = Tile::man_tile(2);
+    winning_player.ron_or_tsumo = WinningMethod::Tsumo;
 
-        assert_eq!(player.callable_tiles.len(), 9);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::West, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::East, red : true} ), true);
-        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::One, red : true} ), true);
+    let interpretations = game.players[2].winning_hand_interpretations();
+
+    let seven_pairs = interpretations.iter().find(|hand| hand.is_seven_pairs()).expect("Seven pairs was not found");
+    assert_eq!(yaku_chiitoitsu(&game.players[2], &game, seven_pairs), 2);
+    assert_eq!(game.players[2].interpretation_fu(&game, seven_pairs, true), 25);
+
+    let sequences = interpretations.iter().find(|hand| ! hand.is_seven_pairs()).expect("Sequences were not found");
+    assert_eq!(yaku_ryanpeikou(&game.players[2], &game, sequences), 3);
+    assert_eq!(yaku_iipeikou(&game.players[2], &game, sequences), 0);
+    assert_eq!(yaku_chiitoitsu(&game.players[2], &game, sequences), 0);
+
+    // ryanpeikou, pinfu, and tsumo is a mangan, which is worth more than chiitoitsu and tsumo
+    assert_eq!(game.players[2].score_hand_basic_points(&game), 2000);
+}
+
+#[test]
+fn test_yaku_terminals()
+{
+    let mut game = Game::default();
+
+    let winning_player = &mut game.players[3];
+
+    winning_player.hand = vec!(
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3),
+        Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3),
+        Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3),
+        Tile::man_tile(9), Tile::man_tile(9),
+    );
+    winning_player.sort_hand();
+
+    winning_player.called_sets = vec!(
+        CalledSet {
+            set : Set::sequence(Tile::sou_tile(7)),
+            call_type : CallTypes::Ron(SetType::Sequence)
+        },
+    );
+
+    winning_player.last_picked_tile = Tile::sou_tile(7);
+    winning_player.ron_or_tsumo = WinningMethod::Ron(0);
+
+    let hand = &game.players[3].winning_hand_interpretations()[0];
+    assert_eq!(hand.wait, WaitType::Penchan);
+    assert_eq!(yaku_junchantaiyao(&game.players[3], &game, hand), 3);
+    assert_eq!(yaku_chantaiyao(&game.players[3], &game, hand), 0);
+    assert_eq!(yaku_sanshoku_doujun(&game.players[3], &game, hand), 2);
+    assert_eq!(yaku_pinfu(&game.players[3], &game, hand), 0);
+    assert_eq!(yaku_honroutou(&game.players[3], &game, hand), 0);
+
+    assert_eq!(game.players[3].score_hand_basic_points(&game), 2000);
+}
+
+#[test]
+fn test_hand_score_non_dealer_tsumo()
+{
+    let mut game = Game::default();
+
+    let winning_player = &mut game.players[1];
+
+    winning_player.hand = vec!(
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::pin_tile(5), Tile::pin_tile(6), Tile::pin_tile(7),
+        Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+        Tile::sou_tile(5), Tile::sou_tile(5),
+    );
+    winning_player.sort_hand();
+
+    winning_player.last_picked_tile = Tile::sou_tile(8);
+    winning_player.ron_or_tsumo = WinningMethod::Tsumo;
+
+    let hand_score = score_hand(&game, 1);
+    assert_eq!(hand_score.hand_yaku, vec![
+        (YakuType::MenzenchinTsumohou, 1),
+        (YakuType::Pinfu, 1),
+        (YakuType::Iipeikou, 1),
+        (YakuType::Tanyao, 1),
+    ]);
+    assert_eq!(hand_score.han, 4);
+    assert_eq!(hand_score.fu, 20);
+    assert_eq!(hand_score.fu_components, vec![(FuComponent::Base, 20)]);
+    assert_eq!(hand_score.limit, HandLimit::NoLimit);
+    assert_eq!(hand_score.basic_points, 1280);
+
+    // the dealer pays double. Each payment is rounded up separately
+    assert_eq!(hand_score.point_changes, [-2600, 5200, -1300, -1300]);
+}
+
+#[test]
+fn test_hand_score_honba_and_riichi_sticks()
+{
+    let mut game = Game { honba : 2, riichi_sticks : 1, ..Game::default() };
+
+    let winning_player = &mut game.players[1];
+
+    winning_player.hand = vec!(
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::pin_tile(5), Tile::pin_tile(6), Tile::pin_tile(7),
+        Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+        Tile::sou_tile(5), Tile::sou_tile(5),
+    );
+    winning_player.sort_hand();
+
+    winning_player.last_picked_tile = Tile::sou_tile(8);
+    winning_player.ron_or_tsumo = WinningMethod::Tsumo;
+
+    // each payer adds 100 per honba, and the winner takes the riichi stick
+    let hand_score = score_hand(&game, 1);
+    assert_eq!(hand_score.honba, 2);
+    assert_eq!(hand_score.riichi_sticks, 1);
+    assert_eq!(hand_score.point_changes, [-2800, 6800, -1500, -1500]);
+
+    // the discarder pays all 300 per honba for ron
+    game.honba = 1;
+    game.riichi_sticks = 2;
+    game.players[1].ron_or_tsumo = WinningMethod::Ron(3);
+
+    let hand_score = score_hand(&game, 1);
+    assert_eq!(hand_score.basic_points, 960);
+    assert_eq!(hand_score.point_changes, [0, 6200, 0, -4200]);
+
+    // paying out clears the pot, and the honba is left for play_round to update
+    scoring::score_points(&mut game, Some(1));
+    assert_eq!(game.riichi_sticks, 0);
+    assert_eq!(game.honba, 1);
+    assert_eq!(game.players[1].points, 25000 + 6200);
+    assert_eq!(game.players[3].points, 25000 - 4200);
+}
+
+#[test]
+fn test_hand_score_dealer_ron_mangan()
+{
+    let mut game = Game::default();
+
+    let winning_player = &mut game.players[0];
+
+    winning_player.hand = vec!(
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3),
+        Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3),
+        Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3),
+        Tile::man_tile(9), Tile::man_tile(9),
+    );
+    winning_player.sort_hand();
+
+    winning_player.called_sets = vec!(
+        CalledSet {
+            set : Set::sequence(Tile::sou_tile(7)),
+            call_type : CallTypes::Ron(SetType::Sequence)
+        },
+    );
+
+    winning_player.last_picked_tile = Tile::sou_tile(7);
+    winning_player.ron_or_tsumo = WinningMethod::Ron(3);
+
+    let hand_score = score_hand(&game, 0);
+    assert_eq!(hand_score.hand_yaku, vec![
+        (YakuType::SanshokuDoujun, 2),
+        (YakuType::Junchantaiyao, 3),
+    ]);
+    assert_eq!(hand_score.fu_components, vec![
+        (FuComponent::Base, 20),
+        (FuComponent::Wait(WaitType::Penchan), 2),
+        (FuComponent::MenzenRon, 10),
+    ]);
+    assert_eq!(hand_score.fu, 40);
+    assert_eq!(hand_score.han, 5);
+    assert_eq!(hand_score.limit, HandLimit::Mangan);
+    assert_eq!(hand_score.basic_points, 2000);
+    assert_eq!(hand_score.point_changes, [12000, 0, 0, -12000]);
+}
+
+#[test]
+fn test_hand_score_dora()
+{
+    let mut game = Game::default();
+    let dora_indicators_idx = game.dora_indicators_idx();
+
+    // kan dora has been revealed, so there are two indicators. 4m makes 5m the dora, 9s makes 1s the dora
+    game.tiles[dora_indicators_idx] = Tile::man_tile(4);
+    game.tiles[dora_indicators_idx + 2] = Tile::sou_tile(9);
+    // ura dora under each indicator. 6p makes 7p the ura dora
+    game.tiles[dora_indicators_idx + 1] = Tile::pin_tile(6);
+    game.tiles[dora_indicators_idx + 3] = Tile { suit : Suit::Honor, value : SuitVal::North, red : false };
+    game.reveal_dora();
+
+    let winning_player = &mut game.players[1];
+
+    winning_player.hand = vec!(
+        Tile::man_tile(3), Tile::man_tile(4), Tile { suit : Suit::Man, value : SuitVal::Five, red : true },
+        Tile::man_tile(5), Tile::man_tile(6), Tile::man_tile(7),
+        Tile::pin_tile(5), Tile::pin_tile(6), Tile::pin_tile(7),
+        Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(4),
+        Tile::sou_tile(8), Tile::sou_tile(8),
+    );
+    winning_player.sort_hand();
+
+    winning_player.last_picked_tile = Tile::sou_tile(4);
+    winning_player.ron_or_tsumo = WinningMethod::Tsumo;
+
+    let hand_score = score_hand(&game, 1);
+    assert_eq!(hand_score.dora, 2);
+    assert_eq!(hand_score.aka_dora, 1);
+    assert_eq!(hand_score.ura_dora, 0);
+    // tsumo, pinfu, tanyao
+    assert_eq!(hand_score.han, 6);
+    assert_eq!(hand_score.limit, HandLimit::Haneman);
+
+    // ura dora only count after riichi
+    game.players[1].riichi = true;
+    let hand_score = score_hand(&game, 1);
+    assert_eq!(hand_score.ura_dora, 1);
+    assert_eq!(hand_score.han, 8);
+    assert_eq!(hand_score.limit, HandLimit::Baiman);
+}
+
+#[test]
+fn test_dead_wall_replacement_draws()
+{
+    let mut game = Game::default();
+    let replacement_tiles_idx = game.replacement_tiles_idx();
+    game.next_tile = replacement_tiles_idx - 2;
+
+    // each kan moves the last live wall tile into the dead wall
+    game.tiles[replacement_tiles_idx] = Tile::man_tile(1);
+    game.tiles[replacement_tiles_idx + 1] = Tile::man_tile(2);
+    assert!(game.can_declare_kan());
+    assert_eq!(game.draw_from_dead_wall(), Tile::man_tile(1));
+    assert_eq!(game.draw_from_dead_wall(), Tile::man_tile(2));
+    assert_eq!(game.dead_wall_idx, replacement_tiles_idx - 2);
+    assert!(game.drew_from_dead_wall);
+
+    // two kans took the last two live tiles, so there's nothing left to draw or kan
+    assert!(game.live_wall_is_empty());
+    assert!(! game.can_declare_kan());
+    assert_eq!(game.draw_next_tile(), None);
+}
+
+#[test]
+fn test_rinshan_kaihou()
+{
+    let mut game = Game::default();
+    let replacement_tiles_idx = game.replacement_tiles_idx();
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = Tile::pin_tile(1);
+
+    let winning_player = &mut game.players[1];
+
+    winning_player.hand = vec!(
+        Tile::pin_tile(2), Tile::pin_tile(3), Tile::pin_tile(4),
+        Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+        Tile::man_tile(3), Tile::man_tile(3), Tile::man_tile(3),
+        Tile::pin_tile(9),
+    );
+    winning_player.sort_hand();
+
+    winning_player.called_sets = vec!(
+        CalledSet {
+            set : Set::kan(Tile::sou_tile(2)),
+            call_type : CallTypes::OpenKan
+        },
+    );
+
+    // the last live tile moved into the dead wall after the kan, but the replacement tile still isn't haitei
+    game.next_tile = game.dead_wall_idx - 1;
+    game.tiles[replacement_tiles_idx] = Tile::pin_tile(9);
+    game.draw_kan_replacement_tile(1);
+    assert_eq!(game.players[1].last_picked_tile, Tile::pin_tile(9));
+    assert!(game.live_wall_is_empty());
+    game.players[1].ron_or_tsumo = WinningMethod::Tsumo;
+
+    let hand_score = score_hand(&game, 1);
+    assert_eq!(hand_score.hand_yaku, vec![(YakuType::RinshanKaihou, 1)]);
+    assert_eq!(game.dora_indicators().len(), 2);
+}
+
+#[test]
+fn test_chankan()
+{
+    let mut game = Game::default();
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = Tile::pin_tile(1);
+
+    // player 0 has a pon of 3 man and drew the fourth
+    game.players[0].hand = vec!(
+        Tile::man_tile(3),
+        Tile::pin_tile(2), Tile::pin_tile(3), Tile::pin_tile(4),
+        Tile::pin_tile(6), Tile::pin_tile(7), Tile::pin_tile(8),
+        Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(4),
+        Tile::sou_tile(9),
+    );
+    game.players[0].called_sets = vec!(
+        CalledSet {
+            set : Set::triplet(Tile::man_tile(3)),
+            call_type : CallTypes::Pon
+        },
+    );
+    game.players[0].update_callable_tiles();
+    assert_eq!(game.players[0].added_kan_tiles(), vec![Tile::man_tile(3)]);
+    game.curr_player_idx = 0;
+
+    // player 2 is waiting on 3 man
+    game.players[2].hand = vec!(
+        Tile::man_tile(1), Tile::man_tile(2),
+        Tile::pin_tile(4), Tile::pin_tile(5), Tile::pin_tile(6),
+        Tile::sou_tile(4), Tile::sou_tile(5), Tile::sou_tile(6),
+        Tile::sou_tile(7), Tile::sou_tile(8), Tile::sou_tile(9),
+        Tile::man_tile(8), Tile::man_tile(8),
+    );
+    game.players[2].sort_hand();
+    game.players[2].update_callable_tiles();
+    game.players[2].check_complete_hand_and_update_waits();
+
+    let turn_result = game.open_added_kan(0, Tile::man_tile(3));
+    assert!(matches!(turn_result, TurnResult::Chankan(NextPlayerOrWin::Winners(winners)) if winners == vec![2]));
+
+    // the kan was never completed
+    assert_eq!(game.players[0].called_sets[0].set.set_type, SetType::Triplet);
+    assert!(! game.players[0].hand.contains(&Tile::man_tile(3)));
+    assert_eq!(game.num_kans, 0);
+
+    let hand_score = score_hand(&game, 2);
+    assert_eq!(hand_score.hand_yaku, vec![(YakuType::Chankan, 1)]);
+}
+
+#[test]
+fn test_multiple_ron()
+{
+    fn game_with_players_waiting_on_3_man(waiting_players : &[usize]) -> Game
+    {
+        let mut game = Game { curr_player_idx : 0, ..Game::default() };
+        game.players[0].discard_pile.push(Tile::man_tile(3));
+
+        for player_idx in waiting_players
+        {
+            let player = &mut game.players[*player_idx];
+            player.hand = vec!(
+                Tile::man_tile(4), Tile::man_tile(5),
+                Tile::pin_tile(4), Tile::pin_tile(5), Tile::pin_tile(6),
+                Tile::sou_tile(4), Tile::sou_tile(5), Tile::sou_tile(6),
+                Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+                Tile::man_tile(8), Tile::man_tile(8),
+            );
+            player.update_callable_tiles();
+            player.check_complete_hand_and_update_waits();
+        }
+
+        game
+    }
+
+    let mut game = game_with_players_waiting_on_3_man(&[1, 3]);
+    let next_or_win = game.execute_call_or_advance_player(Tile::man_tile(3));
+    assert!(matches!(next_or_win, NextPlayerOrWin::Winners(winners) if winners == vec![1, 3]));
+    assert_eq!(game.players[1].ron_or_tsumo, WinningMethod::Ron(0));
+    assert_eq!(game.players[3].ron_or_tsumo, WinningMethod::Ron(0));
+    // pinfu and tanyao at 30 fu
+    assert_eq!(score_hand(&game, 3).point_changes, [-2000, 0, 0, 2000]);
+
+    // only the first winner in turn order gets the honba and riichi sticks
+    game.honba = 2;
+    game.riichi_sticks = 1;
+    let points_before : Vec<i32> = game.players.iter().map(|player| player.points).collect();
+    scoring::score_ron_winners(&mut game, &[1, 3]);
+    let point_changes : Vec<i32> = game.players.iter().zip(points_before).map(|(player, points_before)| player.points - points_before).collect();
+    assert_eq!(point_changes, [-4600, 3600, 0, 2000]);
+    assert_eq!(game.riichi_sticks, 0);
+
+    // head bump goes to the first player in turn order after the discarder
+    let mut game = game_with_players_waiting_on_3_man(&[3, 2]);
+    game.curr_player_idx = 1;
+    game.players[1].discard_pile.push(Tile::man_tile(3));
+    game.rules.double_ron = DoubleRonRule::Atamahane;
+    let next_or_win = game.execute_call_or_advance_player(Tile::man_tile(3));
+    assert!(matches!(next_or_win, NextPlayerOrWin::Winners(winners) if winners == vec![2]));
+    assert_eq!(game.players[3].ron_or_tsumo, WinningMethod::NotWonYet);
+
+    let mut game = game_with_players_waiting_on_3_man(&[1, 2, 3]);
+    let next_or_win = game.execute_call_or_advance_player(Tile::man_tile(3));
+    assert!(matches!(next_or_win, NextPlayerOrWin::AbortiveDraw(AbortiveDrawType::Sanchahou)));
+
+    // without sanchahou, the triple ron rule decides who wins
+    let mut game = game_with_players_waiting_on_3_man(&[1, 2, 3]);
+    game.rules.abortive_draws.sanchahou = false;
+    let next_or_win = game.execute_call_or_advance_player(Tile::man_tile(3));
+    assert!(matches!(next_or_win, NextPlayerOrWin::Winners(winners) if winners == vec![1, 2, 3]));
+
+    let mut game = game_with_players_waiting_on_3_man(&[1, 2, 3]);
+    game.rules.abortive_draws.sanchahou = false;
+    game.rules.triple_ron = TripleRonRule::Atamahane;
+    let next_or_win = game.execute_call_or_advance_player(Tile::man_tile(3));
+    assert!(matches!(next_or_win, NextPlayerOrWin::Winners(winners) if winners == vec![1]));
+}
+
+#[test]
+fn test_riichi_declaration()
+{
+    let mut game = Game { curr_player_idx : 1, next_tile : 60, ..Game::default() };
+
+    // discarding the west wind leaves the hand waiting on 3 or 6 man
+    game.players[1].hand = vec!(
+        Tile::man_tile(4), Tile::man_tile(5),
+        Tile::pin_tile(4), Tile::pin_tile(5), Tile::pin_tile(6),
+        Tile::sou_tile(4), Tile::sou_tile(5), Tile::sou_tile(6),
+        Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+        Tile::man_tile(8), Tile::man_tile(8),
+        Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+    );
+    assert_eq!(game.players[1].tenpai_discard_idxs(), vec![13]);
+    assert!(game.can_declare_riichi(1));
+
+    // not enough points for the deposit
+    game.players[1].points = 900;
+    assert!(! game.can_declare_riichi(1));
+    game.players[1].points = 25000;
+
+    // fewer than four tiles left to draw
+    game.next_tile = game.dead_wall_idx - 3;
+    assert!(! game.can_declare_riichi(1));
+    game.next_tile = 60;
+
+    // the first discard of the hand with no calls is a double riichi
+    game.declare_riichi(1);
+    let discarded_tile = game.player_discard_tile(1, 13);
+    assert!(matches!(game.execute_call_or_advance_player(discarded_tile), NextPlayerOrWin::NextPlayer(2)));
+
+    assert!(game.players[1].double_riichi);
+    assert!(! game.players[1].riichi);
+    assert!(game.players[1].iipatsu);
+    assert_eq!(game.players[1].points, 24000);
+    assert_eq!(game.riichi_sticks, 1);
+    assert!(! game.can_declare_riichi(1));
+
+    // ippatsu is lost on the player's next discard
+    game.players[1].hand.push(Tile::pin_tile(9));
+    game.player_discard_tile(1, 13);
+    assert!(! game.players[1].iipatsu);
+}
+
+#[test]
+fn test_riichi_ippatsu_broken_by_call()
+{
+    let mut game = Game { curr_player_idx : 0, ..Game::default() };
+
+    game.players[0].riichi = true;
+    game.players[0].iipatsu = true;
+    game.players[0].discard_pile.push(Tile::sou_tile(1));
+
+    game.draw_kan_replacement_tile(2);
+    assert!(! game.players[0].iipatsu);
+}
+
+#[test]
+fn test_abortive_draws()
+{
+    let east = Tile { suit : Suit::Honor, value : SuitVal::East, red : false };
+
+    // kyuushu kyuuhai needs nine different terminals and honors on the first uninterrupted draw
+    let mut game = Game::default();
+    game.players[0].hand = vec!(
+        Tile::man_tile(1), Tile::man_tile(9), Tile::pin_tile(1), Tile::pin_tile(9),
+        Tile::sou_tile(1), Tile::sou_tile(9), east, east,
+        Tile { suit : Suit::Honor, value : SuitVal::South, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5),
+    );
+    assert_eq!(game.players[0].num_unique_terminals_and_honors(), 9);
+    assert!(game.can_declare_kyuushu_kyuuhai(0));
+    game.rules.abortive_draws.kyuushu_kyuuhai = false;
+    assert!(! game.can_declare_kyuushu_kyuuhai(0));
+    game.rules.abortive_draws.kyuushu_kyuuhai = true;
+    game.players[0].discard_pile.push(Tile::man_tile(2));
+    assert!(! game.can_declare_kyuushu_kyuuhai(0));
+
+    // suufon renda ends the hand on the fourth discard of the same wind
+    let mut game = Game { curr_player_idx : 3, ..Game::default() };
+    for player in &mut game.players
+    {
+        player.discard_pile.push(east);
+    }
+    assert!(matches!(game.execute_call_or_advance_player(east), NextPlayerOrWin::AbortiveDraw(AbortiveDrawType::SuufonRenda)));
+    game.rules.abortive_draws.suufon_renda = false;
+    assert!(matches!(game.execute_call_or_advance_player(east), NextPlayerOrWin::NextPlayer(0)));
+
+    // suucha riichi ends the hand once the fourth riichi deposit is paid
+    let mut game = Game { curr_player_idx : 3, ..Game::default() };
+    for player in &mut game.players[0..3]
+    {
+        player.riichi = true;
+        player.discard_pile.push(Tile::pin_tile(1));
+    }
+    game.players[3].discard_pile.push(Tile::pin_tile(2));
+    game.declare_riichi(3);
+    game.players[3].riichi = true;
+    assert!(matches!(game.execute_call_or_advance_player(Tile::pin_tile(2)), NextPlayerOrWin::AbortiveDraw(AbortiveDrawType::SuuchaRiichi)));
+    assert_eq!(game.riichi_sticks, 1);
+
+    // sanma has neither, even with every player discarding the same wind or in riichi
+    let mut game = Game { curr_player_idx : 2, ..Game::new(RuleSet::tenhou_sanma()) };
+    for player in &mut game.players
+    {
+        player.discard_pile.push(east);
+    }
+    assert!(matches!(game.execute_call_or_advance_player(east), NextPlayerOrWin::NextPlayer(0)));
+
+    for player in &mut game.players
+    {
+        player.riichi = true;
+    }
+    assert!(matches!(game.execute_call_or_advance_player(east), NextPlayerOrWin::NextPlayer(0)));
+
+    // suukaikan only when the four kans weren't all made by one player
+    let mut game = Game { curr_player_idx : 0, num_kans : 4, ..Game::default() };
+    for tile_num in 1..=4
+    {
+        game.players[0].called_sets.push(CalledSet { call_type : CallTypes::ClosedKan, set : Set::kan(Tile::sou_tile(tile_num)) });
+    }
+    game.players[0].discard_pile.push(Tile::pin_tile(2));
+    assert!(matches!(game.execute_call_or_advance_player(Tile::pin_tile(2)), NextPlayerOrWin::NextPlayer(1)));
+
+    game.players[0].called_sets.pop();
+    game.players[1].called_sets.push(CalledSet { call_type : CallTypes::ClosedKan, set : Set::kan(Tile::sou_tile(4)) });
+    game.players[0].discard_pile.push(Tile::pin_tile(2));
+    assert!(matches!(game.execute_call_or_advance_player(Tile::pin_tile(2)), NextPlayerOrWin::AbortiveDraw(AbortiveDrawType::Suukaikan)));
+}
+
+#[test]
+fn test_nagashi_mangan()
+{
+    fn game_with_nagashi_mangan(nagashi_mangan : NagashiManganRule) -> Game
+    {
+        let mut game = Game { rules : RuleSet { nagashi_mangan, ..RuleSet::default() }, ..Game::default() };
+        game.players[2].discard_pile = vec!(
+            Tile::man_tile(1), Tile::pin_tile(9),
+            Tile { suit : Suit::Honor, value : SuitVal::North, red : false },
+        );
+        game.players[3].discard_pile = vec!(Tile::man_tile(9), Tile::sou_tile(5));
+        game.players[1].tenpai = true;
+
+        game
+    }
+
+    let game = game_with_nagashi_mangan(NagashiManganRule::InsteadOfNotenPayments);
+    assert!(game.players[2].is_nagashi_mangan());
+    assert!(! game.players[3].is_nagashi_mangan());
+    assert_eq!(scoring::score_nagashi_mangan(&game, 2).point_changes, [-4000, -2000, 8000, -2000]);
+
+    // a called terminal doesn't count as a discard, but still breaks nagashi mangan
+    let mut called_game = game_with_nagashi_mangan(NagashiManganRule::InsteadOfNotenPayments);
+    called_game.players[2].tiles_others_called.push(Tile::sou_tile(1));
+    assert!(! called_game.players[2].is_nagashi_mangan());
+
+    let mut game = game_with_nagashi_mangan(NagashiManganRule::InsteadOfNotenPayments);
+    scoring::score_points(&mut game, None);
+    assert_eq!(game.players.iter().map(|player| player.points).collect::<Vec<i32>>(), [21000, 23000, 33000, 23000]);
+
+    let mut game = game_with_nagashi_mangan(NagashiManganRule::AlongsideNotenPayments);
+    scoring::score_points(&mut game, None);
+    assert_eq!(game.players.iter().map(|player| player.points).collect::<Vec<i32>>(), [20000, 26000, 32000, 22000]);
+
+    let mut game = game_with_nagashi_mangan(NagashiManganRule::Disabled);
+    scoring::score_points(&mut game, None);
+    assert_eq!(game.players.iter().map(|player| player.points).collect::<Vec<i32>>(), [24000, 28000, 24000, 24000]);
+}
+
+#[test]
+fn test_rule_sets()
+{
+    let mut game = Game::new(RuleSet::ema());
+    assert!(game.players.iter().all(|player| player.points == 30000));
+    game.set_red_fives();
+    assert_eq!(game.tiles.iter().filter(|tile| tile.red).count(), 0);
+
+    let mut game = Game::new(RuleSet { red_fives_per_suit : 2, ..RuleSet::tenhou() });
+    game.set_red_fives();
+    assert_eq!(game.tiles.iter().filter(|tile| tile.red && tile.suit == Suit::Pin).count(), 2);
+
+    // open tanyao only counts with kuitan
+    let mut game = Game::default();
+    let player = &mut game.players[1];
+    player.hand = vec!(
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::pin_tile(6), Tile::pin_tile(7), Tile::pin_tile(8),
+        Tile::sou_tile(3), Tile::sou_tile(4), Tile::sou_tile(5),
+        Tile::sou_tile(8), Tile::sou_tile(8),
+    );
+    player.called_sets = vec!(CalledSet { set : Set::sequence(Tile::man_tile(6)), call_type : CallTypes::Chii });
+    player.last_picked_tile = Tile::man_tile(4);
+    player.ron_or_tsumo = WinningMethod::Ron(2);
+
+    let hand = &game.players[1].winning_hand_interpretations()[0];
+    assert_eq!(yaku_tanyao(&game.players[1], &game, hand), 1);
+    game.rules.kuitan = false;
+    assert_eq!(yaku_tanyao(&game.players[1], &game, hand), 0);
+
+    // a 13 sided kokushi wait is only a double yakuman when the rules allow it
+    let mut game = Game::default();
+    let player = &mut game.players[1];
+    player.hand = vec!(
+        Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(9), Tile::pin_tile(1), Tile::pin_tile(9), Tile::sou_tile(1), Tile::sou_tile(9),
+        Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::South, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::North, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Green, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Red, red : false },
+    );
+    player.sort_hand();
+    player.last_picked_tile = Tile::man_tile(1);
+    player.ron_or_tsumo = WinningMethod::Tsumo;
+
+    game.rules.double_yakuman = true;
+    assert_eq!(evaluate_hand(&game.players[1], &game).limit, HandLimit::Yakuman(2));
+    game.rules = RuleSet::wrc();
+    assert_eq!(evaluate_hand(&game.players[1], &game).limit, HandLimit::Yakuman(1));
+
+    // Tenhou scores every yakuman hand as a single yakuman
+    assert!(! RuleSet::tenhou().double_yakuman);
+    game.rules = RuleSet::tenhou();
+    assert_eq!(evaluate_hand(&game.players[1], &game).limit, HandLimit::Yakuman(1));
+}
+
+#[test]
+fn test_kiriage_mangan()
+{
+    let mut game = Game::default();
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = Tile::pin_tile(1);
+
+    // open ittsu, honitsu and yakuhai at 30 fu
+    let winning_player = &mut game.players[1];
+    winning_player.hand = vec!(
+        Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+    );
+    winning_player.sort_hand();
+    winning_player.called_sets = vec!(
+        CalledSet { set : Set::sequence(Tile::man_tile(1)), call_type : CallTypes::Chii },
+        CalledSet { set : Set::sequence(Tile::man_tile(7)), call_type : CallTypes::Ron(SetType::Sequence) },
+    );
+    winning_player.last_picked_tile = Tile::man_tile(9);
+    winning_player.ron_or_tsumo = WinningMethod::Ron(2);
+
+    let hand_score = evaluate_hand(&game.players[1], &game);
+    assert_eq!((hand_score.han, hand_score.fu, hand_score.basic_points), (4, 30, 1920));
+    assert_eq!(hand_score.limit, HandLimit::NoLimit);
+
+    game.rules = RuleSet::m_league();
+    let hand_score = evaluate_hand(&game.players[1], &game);
+    assert_eq!(hand_score.basic_points, 2000);
+    assert_eq!(hand_score.limit, HandLimit::Mangan);
+}
+
+#[test]
+fn test_sanma()
+{
+    let mut game = Game::new(RuleSet::tenhou_sanma());
+    assert_eq!(game.num_players(), 3);
+    assert_eq!(game.tiles.len(), 108);
+    assert!(game.players.iter().all(|player| player.points == 35000));
+
+    game.setup_for_hand();
+    assert!(game.players.iter().all(|player| player.hand.len() == PLAYER_HAND_SIZE - 1));
+    assert_eq!(game.tiles_left_in_live_wall(), 108 - DEAD_WALL_SIZE - 39);
+
+    // the old dealer becomes west, since there's no north seat
+    let mut player = Player::default();
+    player.rotate_wind(NUM_SANMA_PLAYERS);
+    assert_eq!(player.seat_wind, SuitVal::West);
+
+    // 1 man indicates the 9 man
+    assert_eq!(game.dora_from_indicator(Tile::man_tile(1)), Tile::man_tile(9));
+
+    // norths set aside are dora, and count again when a north is dora
+    game.players[1].hand = vec![Tile::pin_tile(2), NORTH_TILE];
+    assert!(game.player_can_nuki_dora(1));
+    game.players[1].hand.pop();
+    game.players[1].nuki_dora = 2;
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = Tile { suit : Suit::Honor, value : SuitVal::West, red : false };
+    assert_eq!(game.players[1].hand_dora_in_han(&game), 4);
+
+    // non-dealer tsumo for 1280 basic points
+    let mut game = Game::new(RuleSet::tenhou_sanma());
+    let winning_player = &mut game.players[1];
+    winning_player.hand = vec!(
+        Tile::pin_tile(2), Tile::pin_tile(3), Tile::pin_tile(4),
+        Tile::pin_tile(2), Tile::pin_tile(3), Tile::pin_tile(4),
+        Tile::pin_tile(6), Tile::pin_tile(7), Tile::pin_tile(8),
+        Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+        Tile::sou_tile(5), Tile::sou_tile(5),
+    );
+    winning_player.sort_hand();
+    winning_player.last_picked_tile = Tile::sou_tile(8);
+    winning_player.ron_or_tsumo = WinningMethod::Tsumo;
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = Tile::man_tile(1);
+
+    let hand_score = score_hand(&game, 1);
+    assert_eq!(hand_score.basic_points, 1280);
+    assert_eq!(hand_score.point_changes, [-2600, 3900, -1300]);
+
+    game.rules.sanma = Some(SanmaTsumoPayment::NorthBisection);
+    assert_eq!(score_hand(&game, 1).point_changes, [-3200, 5200, -2000]);
+}
+
+#[test]
+fn test_game_end_conditions()
+{
+    fn set_points(game : &mut Game, points : [i32; NUM_PLAYERS])
+    {
+        for (player, points) in game.players.iter_mut().zip(points)
+        {
+            player.points = points;
+        }
+    }
+
+    let mut game = Game::default();
+
+    // bust
+    set_points(&mut game, [40000, 35000, 25100, -100]);
+    assert!(game.game_is_over(RepeatHand::RotateWinds, true));
+    game.rules.tobi = false;
+    assert!(! game.game_is_over(RepeatHand::RotateWinds, true));
+    game.rules.tobi = true;
+
+    // the last hand of south. The dealer is player 3
+    game.round_wind = SuitVal::South;
+    game.hand_in_round = 3;
+    for (player, seat_wind) in game.players.iter_mut().zip([SuitVal::South, SuitVal::West, SuitVal::North, SuitVal::East])
+    {
+        player.seat_wind = seat_wind;
+    }
+
+    set_points(&mut game, [28000, 24000, 24000, 24000]);
+    // no one reached 30000, so the game goes into west
+    assert!(! game.game_is_over(RepeatHand::RotateWinds, true));
+
+    set_points(&mut game, [31000, 24000, 21000, 24000]);
+    assert!(game.game_is_over(RepeatHand::RotateWinds, true));
+
+    // agari-yame needs the dealer in first place
+    assert!(! game.game_is_over(RepeatHand::DealerWon, false));
+    set_points(&mut game, [24000, 24000, 21000, 31000]);
+    assert!(game.game_is_over(RepeatHand::DealerWon, false));
+    assert!(! game.game_is_over(RepeatHand::DealerTenpai, false));
+    game.rules.agari_yame = false;
+    assert!(! game.game_is_over(RepeatHand::DealerWon, false));
+
+    // the west round ends as soon as someone reaches 30000
+    game.round_wind = SuitVal::West;
+    game.hand_in_round = 0;
+    set_points(&mut game, [29000, 24000, 23000, 24000]);
+    assert!(! game.game_is_over(RepeatHand::RotateWinds, true));
+    set_points(&mut game, [30000, 24000, 22000, 24000]);
+    assert!(game.game_is_over(RepeatHand::RotateWinds, true));
+}
+
+#[test]
+fn test_placements()
+{
+    let mut game = Game { first_dealer_idx : 2, ..Game::default() };
+    for (player, points) in game.players.iter_mut().zip([20000, 30000, 20000, 30000])
+    {
+        player.points = points;
+    }
+
+    // ties go to the player closest to the first dealer in turn order
+    assert_eq!(game.placements(), vec![3, 1, 2, 0]);
+}
+
+#[test]
+fn test_seeded_games_are_reproducible()
+{
+    let mut game = Game::with_seed(RuleSet::tenhou(), 1234);
+    let mut same_seed_game = Game::with_seed(RuleSet::tenhou(), 1234);
+    let mut other_seed_game = Game::with_seed(RuleSet::tenhou(), 4321);
+    assert_eq!(game.seed, 1234);
+
+    // every hand of the game is shuffled the same way
+    for _ in 0..3
+    {
+        game.setup_for_hand();
+        same_seed_game.setup_for_hand();
+        other_seed_game.setup_for_hand();
+
+        assert!(game.tiles == same_seed_game.tiles);
+        assert!(game.tiles != other_seed_game.tiles);
+        assert!(game.players.iter().zip(&same_seed_game.players).all(|(player, same_seed_player)| player.hand == same_seed_player.hand));
+    }
+
+    // as are the AI's random choices
+    let mut simple_ai = AIAlgorithm::SimpleDiscardAlwaysCall;
+    let hand = game.players[1].hand.clone();
+    let mut rng = game.rng.clone();
+    let discards : Vec<usize> = (0..10).map(|_| simple_ai.choose_discard(&SeatView::new(&game, 1), &mut rng)).collect();
+    same_seed_game.players[1].hand = hand;
+    let mut same_seed_rng = same_seed_game.rng.clone();
+    let same_seed_discards : Vec<usize> = (0..10).map(|_| simple_ai.choose_discard(&SeatView::new(&same_seed_game, 1), &mut same_seed_rng)).collect();
+    assert_eq!(discards, same_seed_discards);
+}
+
+#[test]
+fn test_tenhou_wall_order()
+{
+    assert!(Game::with_tenhou_seed(RuleSet::tenhou(), "not a seed").is_none());
+    assert!(Game::with_tenhou_seed(RuleSet::tenhou_sanma(), "").is_none());
+
+    // TODO: The tile ids, deal order and dead wall positions here are only checked against Tenhou's ids in order, not a real game.
+    // Add a test from a published Tenhou log's SHUFFLE seed, asserting the first hand's haipai, first draws and dora indicator
+    // through Game::with_tenhou_seed
+
+    // the second player deals. With Tenhou's tile ids in order, the back of the wall is dealt first
+    let mut game = Game::new(RuleSet::tenhou());
+    for (player, seat_wind) in game.players.iter_mut().zip([SuitVal::North, SuitVal::East, SuitVal::South, SuitVal::West])
+    {
+        player.set_seat_wind(seat_wind);
+    }
+
+    let yama : Vec<usize> = (0..NUM_GAME_TILES).collect();
+    game.tiles = game.tiles_from_tenhou_yama(&yama);
+    game.next_tile = 0;
+    game.divy_tiles_to_players();
+    game.dead_wall_idx = game.replacement_tiles_idx();
+    game.num_dora_indicators = 1;
+
+    let red_dragon = Tile { suit : Suit::Honor, value : SuitVal::Red, red : false };
+    let west = Tile { suit : Suit::Honor, value : SuitVal::West, red : false };
+    let dealer_hand = &game.players[1].hand;
+    assert_eq!(dealer_hand.iter().filter(|tile| **tile == red_dragon).count(), 4);
+    assert_eq!(dealer_hand.iter().filter(|tile| **tile == west).count(), 4);
+    assert_eq!(dealer_hand.iter().filter(|tile| **tile == Tile::sou_tile(8)).count(), 4);
+    assert!(dealer_hand.contains(&Tile::sou_tile(4)));
+
+    // 52 tiles are dealt, so the dealer's first draw is yama[83]. The dead wall is yama[0..14]
+    assert_eq!(game.draw_next_tile(), Some(tile_from_tenhou_id(83, 1)));
+    assert_eq!(game.dora_indicators(), vec![Tile::man_tile(2)]);
+    assert_eq!(game.ura_dora_indicators(), vec![Tile::man_tile(2)]);
+    assert_eq!(game.draw_from_dead_wall(), Tile::man_tile(1));
+    assert_eq!(game.tiles.len(), NUM_GAME_TILES);
+
+    // the wall hash only changes with the wall
+    let wall_hash = game.wall_hash();
+    assert_eq!(wall_hash.len(), 128);
+    assert_eq!(wall_hash, game.wall_hash());
+    game.tiles.swap(0, NUM_GAME_TILES - 1);
+    assert_ne!(wall_hash, game.wall_hash());
+}
+
+#[test]
+fn test_scripted_wall_haitei()
+{
+    let honor = |value : SuitVal| Tile { suit : Suit::Honor, value, red : false };
+
+    // the second player waits on 1p or 4p, and only draws the 4p as the last tile of the live wall
+    let winning_hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::man_tile(7), Tile::man_tile(8), Tile::man_tile(9), Tile::pin_tile(2), Tile::pin_tile(3), Tile::sou_tile(9), Tile::sou_tile(9),
+    ];
+    let mut winner_draws : Vec<Tile> = [SuitVal::North, SuitVal::East, SuitVal::South, SuitVal::West].iter()
+        .flat_map(|value| vec![honor(*value); 4])
+        .collect();
+    winner_draws.push(honor(SuitVal::Red));
+    winner_draws.push(Tile::pin_tile(4));
+
+    // everyone else holds and draws tiles the winner can't call. The rest of the 1p and 4p are in the dead wall
+    let mut other_tiles = vec![];
+    for value in 1..=8
+    {
+        other_tiles.append(&mut vec![Tile::sou_tile(value); 4]);
+    }
+    other_tiles.append(&mut vec![Tile::sou_tile(9); 2]);
+    other_tiles.append(&mut vec![Tile::pin_tile(2); 3]);
+    other_tiles.append(&mut vec![Tile::pin_tile(3); 3]);
+    for value in 5..=9
+    {
+        other_tiles.append(&mut vec![Tile::pin_tile(value); 4]);
+    }
+    for value in 1..=9
+    {
+        other_tiles.append(&mut vec![Tile::man_tile(value); 3]);
+    }
+    other_tiles.append(&mut vec![honor(SuitVal::Red); 3]);
+    other_tiles.push(honor(SuitVal::White));
+
+    let mut other_tiles = other_tiles.into_iter();
+    let hands = vec![
+        other_tiles.by_ref().take(13).collect(),
+        winning_hand,
+        other_tiles.by_ref().take(13).collect(),
+        other_tiles.by_ref().take(13).collect(),
+    ];
+
+    let mut winner_draws = winner_draws.into_iter();
+    let draws : Vec<Tile> = (0..NUM_GAME_TILES - DEAD_WALL_SIZE - 52)
+        .map(|turn| if turn % 4 == 1 { winner_draws.next() } else { other_tiles.next() }.unwrap())
+        .collect();
+
+    let mut game = Game::with_hands_and_draws(RuleSet::tenhou(), hands, draws);
+    for (player_idx, player) in game.players.iter_mut().enumerate()
+    {
+        player.is_human = false;
+        player.ai_algorithm = if player_idx == 1 { AIAlgorithm::SimpleDiscardAlwaysCall } else { AIAlgorithm::DumbAsBricks };
+    }
+
+    assert!(game.play_hand() == RepeatHand::RotateWinds);
+    assert!(game.live_wall_is_empty());
+    assert!(game.players[1].is_in_riichi());
+    assert!(game.players[1].points > RuleSet::tenhou().starting_points);
+
+    let hand_score = scoring::evaluate_hand(&game.players[1], &game);
+    assert!(hand_score.hand_yaku.iter().any(|(yaku, _)| *yaku == YakuType::HaiteiRaoyue));
+    assert!(hand_score.hand_yaku.iter().any(|(yaku, _)| *yaku == YakuType::MenzenchinTsumohou));
+
+    // the wall can also be given tile by tile
+    let wall = game.tiles.clone();
+    let mut game = Game::with_wall(RuleSet::tenhou(), wall.clone());
+    game.setup_for_hand();
+    assert!(game.tiles == wall);
+    assert_eq!(game.players[1].hand, {
+        let mut winning_hand : Vec<Tile> = (0..13).map(|i| wall[i * 4 + 1]).collect();
+        winning_hand.sort();
+        winning_hand
+    });
+
+    // only the first hand is scripted
+    game.setup_for_hand();
+    assert!(game.tiles != wall);
+}
+
+#[test]
+fn test_hand_after_ron()
+{
+    /// discards whatever it draws and rons whenever it can. Nothing else is called, so it always discards from 14 tiles
+    struct TsumogiriRon;
+
+    impl AIStrategy for TsumogiriRon {
+        fn choose_discard(&mut self, view : &SeatView, rng : &mut StdRng) -> usize
+        {
+            assert_eq!(view.hand().len(), PLAYER_HAND_SIZE);
+            view.hand().len() - 1
+        }
+
+        fn choose_call(&mut self, view : &SeatView, tile : Tile, possible_calls : &[CalledSet], rng : &mut StdRng) -> Option<CalledSet>
+        {
+            possible_calls.iter().find(|call| matches!(call.call_type, CallTypes::Ron(_))).cloned()
+        }
+    }
+
+    let honor = |value : SuitVal| Tile { suit : Suit::Honor, value, red : false };
+
+    // after a go around of safe draws, the dealer draws the 3m that the second player is waiting on, and discards it
+    let hands = vec![
+        vec![
+            Tile::man_tile(1), Tile::man_tile(4), Tile::man_tile(7), Tile::pin_tile(2), Tile::pin_tile(5), Tile::pin_tile(8),
+            Tile::sou_tile(3), Tile::sou_tile(6), Tile::sou_tile(9),
+            honor(SuitVal::East), honor(SuitVal::South), honor(SuitVal::West), honor(SuitVal::North),
+        ],
+        vec![
+            Tile::man_tile(4), Tile::man_tile(5), Tile::pin_tile(4), Tile::pin_tile(5), Tile::pin_tile(6),
+            Tile::sou_tile(4), Tile::sou_tile(5), Tile::sou_tile(6), Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+            Tile::man_tile(8), Tile::man_tile(8),
+        ],
+        vec![
+            Tile::sou_tile(1), Tile::sou_tile(4), Tile::sou_tile(7), Tile::pin_tile(3), Tile::pin_tile(6), Tile::pin_tile(9),
+            Tile::man_tile(2), Tile::man_tile(5), Tile::man_tile(9),
+            honor(SuitVal::White), honor(SuitVal::White), honor(SuitVal::Green), honor(SuitVal::Green),
+        ],
+        vec![
+            Tile::pin_tile(1), Tile::pin_tile(7), Tile::pin_tile(9), Tile::sou_tile(2), Tile::sou_tile(8), Tile::sou_tile(9),
+            Tile::man_tile(1), Tile::man_tile(6), Tile::man_tile(9),
+            honor(SuitVal::East), honor(SuitVal::East), honor(SuitVal::Red), honor(SuitVal::Red),
+        ],
+    ];
+    let mut game = Game::with_hands_and_draws(RuleSet::tenhou(), hands,
+        vec![Tile::pin_tile(9), Tile::sou_tile(9), Tile::man_tile(9), Tile::pin_tile(1), Tile::man_tile(3)]);
+    for player_idx in 0..game.num_players()
+    {
+        game.set_player_strategy(player_idx, Box::new(TsumogiriRon));
+    }
+
+    assert!(game.play_hand() == RepeatHand::RotateWinds);
+    assert_eq!(game.players[1].ron_or_tsumo, WinningMethod::Ron(0));
+
+    // the next hand starts fresh, so the dealer draws before their first discard and every discard is from 14 tiles
+    game.play_hand();
+}
+
+#[test]
+fn test_player_discard_ukeire()
+{
+    let mut game = Game::default();
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = Tile::man_tile(7);
+
+    // 23456m 789p 123s 99s after drawing a west
+    game.players[0].hand = vec![
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::pin_tile(7), Tile::pin_tile(8), Tile::pin_tile(9), Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3),
+        Tile::sou_tile(9), Tile::sou_tile(9), Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+    ];
+    game.players[1].discard_pile = vec![Tile::man_tile(1)];
+    game.players[2].called_sets = vec![CalledSet { call_type : CallTypes::Pon, set : Set::triplet(Tile::man_tile(4)) }];
+    game.players[3].nuki_dora = 2;
+
+    let visible_tile_counts = game.visible_tile_counts();
+    assert_eq!(visible_tile_counts[Tile::man_tile(1).kind_idx()], 1);
+    assert_eq!(visible_tile_counts[Tile::man_tile(4).kind_idx()], 3);
+    assert_eq!(visible_tile_counts[Tile::man_tile(7).kind_idx()], 1);
+    assert_eq!(visible_tile_counts[NORTH_TILE.kind_idx()], 2);
+    assert_eq!(visible_tile_counts.iter().map(|count| *count as usize).sum::<usize>(), 7);
+
+    // discarding the west is tenpai on 1m, 4m or 7m, though every 4m has been seen
+    let discards = game.player_discard_ukeire(0);
+    assert_eq!(discards[0].discard, Tile { suit : Suit::Honor, value : SuitVal::West, red : false });
+    assert_eq!(discards[0].accepted_tiles, vec![(Tile::man_tile(1), 3), (Tile::man_tile(4), 0), (Tile::man_tile(7), 3)]);
+    assert!(discards.windows(2).all(|pair| (pair[0].shanten, std::cmp::Reverse(pair[0].num_accepted())) <= (pair[1].shanten, std::cmp::Reverse(pair[1].num_accepted()))));
+}
+
+#[test]
+fn test_final_results()
+{
+    // tenhou: 30000 return points with 25000 starting points gives 20000 oka, and 10-20 uma
+    let mut game = Game { first_dealer_idx : 0, riichi_sticks : 1, ..Game::new(RuleSet::tenhou()) };
+    for (player, points) in game.players.iter_mut().zip([8000, 31000, 42000, 18000])
+    {
+        player.points = points;
+    }
+
+    let results = scoring::final_results(&game);
+    assert_eq!(results.iter().map(|result| result.player_idx).collect::<Vec<usize>>(), vec![2, 1, 3, 0]);
+    assert_eq!(results.iter().map(|result| result.placement).collect::<Vec<usize>>(), vec![1, 2, 3, 4]);
+
+    // first place takes the leftover riichi stick
+    assert_eq!(results[0], scoring::FinalResult {
+        player_idx : 2,
+        placement : 1,
+        raw_score : 43000,
+        return_score : 13000,
+        oka : 20000,
+        uma : 20000,
+        final_score : 53000,
+    });
+    assert_eq!(results.iter().map(|result| result.final_score).collect::<Vec<i32>>(), vec![53000, 11000, -22000, -42000]);
+    assert_eq!(results.iter().map(|result| result.final_score).sum::<i32>(), 0);
+
+    // ema: no oka, and 5-15 uma
+    let mut game = Game { first_dealer_idx : 0, ..Game::new(RuleSet::ema()) };
+    for (player, points) in game.players.iter_mut().zip([40000, 35000, 25000, 20000])
+    {
+        player.points = points;
+    }
+
+    let results = scoring::final_results(&game);
+    assert!(results.iter().all(|result| result.oka == 0));
+    assert_eq!(results.iter().map(|result| result.final_score).collect::<Vec<i32>>(), vec![25000, 10000, -10000, -25000]);
+
+    // sanma only has uma for first and last place
+    let mut game = Game { first_dealer_idx : 0, ..Game::new(RuleSet::tenhou_sanma()) };
+    for (player, points) in game.players.iter_mut().zip([50000, 35000, 20000])
+    {
+        player.points = points;
+    }
+
+    let results = scoring::final_results(&game);
+    assert_eq!(results.iter().map(|result| result.uma).collect::<Vec<i32>>(), vec![15000, 0, -15000]);
+    assert_eq!(results.iter().map(|result| result.final_score).sum::<i32>(), 0);
+}
+
+#[test]
+fn test_hand_score_yakuman()
+{
+    let mut game = Game::default();
+
+    let winning_player = &mut game.players[2];
+
+    winning_player.hand = vec!(
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::White, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Green, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Green, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Green, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Red, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Red, red : false },
+        Tile { suit : Suit::Honor, value : SuitVal::Red, red : false },
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4),
+        Tile::pin_tile(9), Tile::pin_tile(9),
+    );
+    winning_player.sort_hand();
+
+    winning_player.last_picked_tile = Tile::man_tile(4);
+    winning_player.ron_or_tsumo = WinningMethod::Tsumo;
+
+    let hand_score = score_hand(&game, 2);
+    assert_eq!(hand_score.hand_yaku, vec![(YakuType::Daisangen, 1)]);
+    assert_eq!(hand_score.limit, HandLimit::Yakuman(1));
+    assert_eq!(hand_score.basic_points, 8000);
+    assert_eq!(hand_score.point_changes, [-16000, -8000, 32000, -8000]);
+}
+
+
+
+#[test]
+fn test_callable_tiles()
+{
+    {
+        let mut player = Player {
+            hand : vec![
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+            ],
+            ..Player::default()
+        };
+
+        player.sort_hand();
+        player.update_callable_tiles();
+
+        assert_eq!(player.callable_tiles.len(), 1);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::West, red : true} ), true);
+
+        let mut player = Player {
+            hand : vec![
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+            ],
+            ..Player::default()
+        };
+    }
+
+    {
+        let mut player = Player {
+            hand : vec![
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+                Tile::man_tile(2),
+                Tile::man_tile(3),
+                Tile::man_tile(4),
+                Tile::man_tile(5),
+                Tile::man_tile(6),
+            ],
+            ..Player::default()
+        };
+
+        player.sort_hand();
+        player.update_callable_tiles();
+
+        assert_eq!(player.callable_tiles.len(), 9);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::West, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::East, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::One, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Two, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Three, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Four, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Five, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Six, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Seven, red : true} ), true);
+    }
+
+    {
+        let mut player = Player {
+            hand : vec![
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+                Tile { suit : Suit::Honor, value : SuitVal::East, red : false },
+                Tile::man_tile(2),
+                Tile::man_tile(3),
+                Tile::man_tile(4),
+                Tile::man_tile(5),
+                Tile::man_tile(6),
+            ],
+            tenpai : true,
+            ..Player::default()
+        };
+
+        player.sort_hand();
+        player.update_callable_tiles();
+
+        assert_eq!(player.callable_tiles.len(), 9);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::West, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Honor, value : SuitVal::East, red : true} ), true);
+        assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::One, red : true} ), true);
         assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Two, red : true} ), true);
         assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Three, red : true} ), true);
         assert_eq!(player.callable_tiles.contains_key( &Tile { suit : Suit::Man, value : SuitVal::Four, red : true} ), true);
@@ -1318,3 +3484,213 @@ fn test_callable_tiles()
         assert_eq!(*player.callable_tiles.entry(Tile::sou_tile(8)).or_default(), chii_pon_or_hand_kans_call);
     }
 }
+
+#[test]
+fn test_ron_on_special_waits()
+{
+    let east = Tile { suit : Suit::Honor, value : SuitVal::East, red : false };
+
+    // a 13 sided kokushi wait rons on any of the thirteen, and takes only the discarded tile
+    let mut game = Game { curr_player_idx : 0, ..Game::default() };
+    game.players[0].discard_pile.push(east);
+    let player = &mut game.players[1];
+    player.hand = all_tile_kinds().into_iter().filter(|tile| tile.is_honor_or_terminal()).collect();
+    player.update_callable_tiles();
+    player.check_complete_hand_and_update_waits();
+    assert_eq!(player.winning_call_tiles.len(), 13);
+
+    let next_or_win = game.execute_call_or_advance_player(east);
+    assert!(matches!(next_or_win, NextPlayerOrWin::Winners(winners) if winners == vec![1]));
+    assert_eq!(game.players[1].hand.len(), 13);
+    assert_eq!(yakuman_kokushi_musou(&game.players[1], &game), 2);
+
+    // seven pairs waits on its single tile, and is furiten once that tile was discarded
+    let seven_pairs_hand = vec!(
+        Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(5), Tile::man_tile(5),
+        Tile::pin_tile(2), Tile::pin_tile(2), Tile::pin_tile(7), Tile::pin_tile(7),
+        Tile::sou_tile(3), Tile::sou_tile(3), Tile::sou_tile(9), Tile::sou_tile(9),
+        east,
+    );
+
+    let mut game = Game { curr_player_idx : 0, ..Game::default() };
+    game.players[0].discard_pile.push(east);
+    let player = &mut game.players[1];
+    player.hand = seven_pairs_hand.clone();
+    player.update_callable_tiles();
+    player.check_complete_hand_and_update_waits();
+    assert!(player.tenpai && ! player.furiten);
+    assert_eq!(player.winning_call_tiles, vec![east]);
+
+    let next_or_win = game.execute_call_or_advance_player(east);
+    assert!(matches!(next_or_win, NextPlayerOrWin::Winners(winners) if winners == vec![1]));
+    let hand = &game.players[1].winning_hand_interpretations()[0];
+    assert_eq!(game.players[1].interpretation_fu(&game, hand, true), 25);
+
+    let player = &mut game.players[2];
+    player.hand = seven_pairs_hand;
+    player.discard_pile.push(east);
+    player.check_complete_hand_and_update_waits();
+    assert!(player.tenpai && player.furiten);
+
+    // a nine sided chuuren wait is a double yakuman whichever tile it wins on
+    let mut game = Game { curr_player_idx : 0, ..Game::default() };
+    game.players[0].discard_pile.push(Tile::pin_tile(5));
+    let player = &mut game.players[1];
+    player.hand = vec![Tile::pin_tile(1), Tile::pin_tile(1), Tile::pin_tile(9), Tile::pin_tile(9)];
+    player.hand.extend((1..=9).map(Tile::pin_tile));
+    player.sort_hand();
+    player.update_callable_tiles();
+    player.check_complete_hand_and_update_waits();
+    assert_eq!(player.winning_call_tiles.len(), 9);
+
+    let next_or_win = game.execute_call_or_advance_player(Tile::pin_tile(5));
+    assert!(matches!(next_or_win, NextPlayerOrWin::Winners(winners) if winners == vec![1]));
+    assert_eq!(yakuman_chuuren_poutou(&game.players[1], &game), 2);
+}
+
+#[test]
+fn test_efficient_ai_discard()
+{
+    let west = Tile { suit : Suit::Honor, value : SuitVal::West, red : false };
+    let north = Tile { suit : Suit::Honor, value : SuitVal::North, red : false };
+
+    // 123m 456m 78p 23s 55p with a lone west and north. Either wind is the same for shanten and ukeire
+    let mut game = Game::default();
+    let mut efficient_ai = AIAlgorithm::EfficientDiscardAlwaysCall;
+    game.players[1].hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::pin_tile(7), Tile::pin_tile(8), Tile::sou_tile(2), Tile::sou_tile(3), Tile::pin_tile(5), Tile::pin_tile(5),
+        west, north,
+    ];
+
+    let mut rng = game.rng.clone();
+    let discard_idx = efficient_ai.choose_discard(&SeatView::new(&game, 1), &mut rng);
+    assert!(game.players[1].hand[discard_idx] == west || game.players[1].hand[discard_idx] == north);
+
+    // but a dora west is kept
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = Tile { suit : Suit::Honor, value : SuitVal::South, red : false };
+    let discard_idx = efficient_ai.choose_discard(&SeatView::new(&game, 1), &mut rng);
+    assert_eq!(game.players[1].hand[discard_idx], north);
+}
+
+#[test]
+fn test_defensive_ai_discard()
+{
+    let west = Tile { suit : Suit::Honor, value : SuitVal::West, red : false };
+    let north = Tile { suit : Suit::Honor, value : SuitVal::North, red : false };
+
+    let mut game = Game::default();
+    let mut defensive_ai = AIAlgorithm::DefensiveDiscardAlwaysCall;
+    game.players[1].hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::pin_tile(7), Tile::pin_tile(8), Tile::sou_tile(2), Tile::sou_tile(3), Tile::pin_tile(5), Tile::pin_tile(5),
+        west, north,
+    ];
+    game.players[2].discard_pile = vec![Tile::sou_tile(3), Tile::pin_tile(1)];
+    let mut rng = game.rng.clone();
+
+    // without a threat it plays for efficiency
+    let discard_idx = defensive_ai.choose_discard(&SeatView::new(&game, 1), &mut rng);
+    assert!(game.players[1].hand[discard_idx] == west || game.players[1].hand[discard_idx] == north);
+
+    // a cheap hand one from tenpai folds against riichi with the genbutsu
+    game.players[2].riichi = true;
+    let discard_idx = defensive_ai.choose_discard(&SeatView::new(&game, 1), &mut rng);
+    assert_eq!(game.players[1].hand[discard_idx], Tile::sou_tile(3));
+
+    // but a tenpai hand pushes, rather than breaking up 34s for the 3s
+    game.players[1].hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::pin_tile(7), Tile::pin_tile(8), Tile::pin_tile(9), Tile::sou_tile(3), Tile::sou_tile(4), Tile::pin_tile(5), Tile::pin_tile(5),
+        west,
+    ];
+    let discard_idx = defensive_ai.choose_discard(&SeatView::new(&game, 1), &mut rng);
+    assert_eq!(game.players[1].hand[discard_idx], west);
+}
+
+#[test]
+fn test_smart_ai_calls()
+{
+    let red_dragon = Tile { suit : Suit::Honor, value : SuitVal::Red, red : false };
+
+    fn game_with_player_1_hand(hand : Vec<Tile>) -> Game
+    {
+        let mut game = Game { curr_player_idx : 0, ..Game::default() };
+        game.players[1].hand = hand;
+        game.players[1].sort_hand();
+        game.players[1].update_callable_tiles();
+        game.players[1].check_complete_hand_and_update_waits();
+        game
+    }
+
+    fn smart_call(game : &Game, tile : Tile) -> Option<CalledSet>
+    {
+        let possible_calls = Player::possible_calls_on_discard(1, tile, game);
+        AIAlgorithm::DefensiveDiscardSmartCall.choose_call(&SeatView::new(game, 1), tile, &possible_calls, &mut game.rng.clone())
+    }
+
+    // a pon of yakuhai is a yaku and gets closer to tenpai
+    let game = game_with_player_1_hand(vec![
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::pin_tile(3), Tile::pin_tile(4),
+        Tile::pin_tile(7), Tile::pin_tile(8), Tile::sou_tile(1), Tile::sou_tile(1), Tile::sou_tile(5),
+        Tile { suit : Suit::Honor, value : SuitVal::West, red : false }, red_dragon, red_dragon,
+    ]);
+    let call = smart_call(&game, red_dragon);
+    assert!(matches!(call, Some(CalledSet { call_type : CallTypes::Pon, .. })));
+
+    // and with the red dragon pair still to pon, so is a chii
+    let call = smart_call(&game, Tile::pin_tile(5));
+    assert!(matches!(call, Some(CalledSet { call_type : CallTypes::Chii, .. })));
+
+    // a pon of 1s with nothing else would leave the hand without a yaku
+    let game = game_with_player_1_hand(vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::pin_tile(7), Tile::pin_tile(8), Tile::pin_tile(9),
+        Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(1), Tile::sou_tile(1), Tile::pin_tile(5),
+        Tile { suit : Suit::Honor, value : SuitVal::West, red : false }, Tile { suit : Suit::Honor, value : SuitVal::North, red : false },
+    ]);
+    assert!(game.players[1].callable_tiles[&Tile::sou_tile(1)].pon);
+    assert!(smart_call(&game, Tile::sou_tile(1)).is_none());
+
+    // a closed hand without a yaku can't ron on its kanchan
+    let game = game_with_player_1_hand(vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::pin_tile(7), Tile::pin_tile(8), Tile::pin_tile(9),
+        Tile::sou_tile(4), Tile::sou_tile(5), Tile::sou_tile(6), Tile::man_tile(9), Tile::man_tile(9),
+        Tile::sou_tile(1), Tile::sou_tile(3),
+    ]);
+    assert!(game.players[1].callable_tiles[&Tile::sou_tile(2)].ron);
+    assert!(smart_call(&game, Tile::sou_tile(2)).is_none());
+
+    // a cheap tanyao ron that still ends the game in last place is passed on
+    let tanyao_hand = vec![
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::pin_tile(6), Tile::pin_tile(7), Tile::pin_tile(8),
+        Tile::sou_tile(4), Tile::sou_tile(5), Tile::sou_tile(6), Tile::man_tile(8), Tile::man_tile(8),
+        Tile::sou_tile(2), Tile::sou_tile(4),
+    ];
+    let mut game = game_with_player_1_hand(tanyao_hand.clone());
+    assert!(smart_call(&game, Tile::sou_tile(3)).is_some());
+
+    game.round_wind = SuitVal::South;
+    game.hand_in_round = game.num_players() - 1;
+    for player in &mut game.players
+    {   player.points = 30000;   }
+    game.players[1].points = 10000;
+    assert!(smart_call(&game, Tile::sou_tile(3)).is_none());
+
+    // but not when it climbs out of last
+    game.players[1].points = 29000;
+    assert!(smart_call(&game, Tile::sou_tile(3)).is_some());
+
+    // or when no one has reached 30000, so the game goes on into the west round
+    for player in &mut game.players
+    {   player.points = 29000;   }
+    game.players[1].points = 10000;
+    assert!(smart_call(&game, Tile::sou_tile(3)).is_some());
+
+    // the last hand of the west round ends the game even though no one has
+    game.round_wind = SuitVal::West;
+    game.hand_in_round = 0;
+    assert!(smart_call(&game, Tile::sou_tile(3)).is_some());
+    game.hand_in_round = game.num_players() - 1;
+    assert!(smart_call(&game, Tile::sou_tile(3)).is_none());
+}
@@ -0,0 +1,266 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+use crate::mahjong::*;
+
+/// chance of dealing in to a threat for each point of tile_danger. The most dangerous tiles deal in about one time in six
+const DEAL_IN_CHANCE_PER_DANGER : f64 = 0.02;
+
+/// points assumed lost when dealing in to a threat. About what an average riichi hand is worth
+const DEAL_IN_POINTS : i32 = 6000;
+
+/// what the Monte Carlo AI is trying to get the most of
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MonteCarloGoal {
+    /// the points it expects to win this hand
+    Points,
+    /// the best expected placement once this hand is over, then points
+    Placement,
+}
+
+/// How hard the Monte Carlo AI thinks. Every different discard is played out num_rollouts times for search_depth draws,
+/// so both trade strength for time
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MonteCarloSettings {
+    /// how many of its own draws are simulated after each discard
+    pub search_depth : usize,
+    /// how many hidden hands and walls are sampled
+    pub num_rollouts : usize,
+    pub goal : MonteCarloGoal,
+}
+
+impl Default for MonteCarloSettings {
+    fn default() -> Self {
+        MonteCarloSettings {
+            search_depth : 6,
+            num_rollouts : 50,
+            goal : MonteCarloGoal::Points,
+        }
+    }
+}
+
+/// the expected outcome of one discard, over every rollout
+struct DiscardEstimate {
+    discard_idx : usize,
+    points : f64,
+    placement : f64,
+}
+
+/// Picks a discard by sampling the tiles the seat can't see into the other hands and the wall, then playing out the seat's next
+/// search_depth draws after each discard. Along the way the hand discards for the fewest shanten, and wins with tsumo whenever
+/// it has a yaku. Other players only come into it through the chance of dealing in to a threat, so a safe discard that goes back
+/// in shanten can be worth more than pushing
+pub fn monte_carlo_discard_idx(view : &SeatView, settings : &MonteCarloSettings, rng : &mut StdRng) -> usize
+{
+    let mut seen_counts = view.visible_tile_counts();
+    for tile in view.hand()
+    {   seen_counts[tile.kind_idx()] += 1;   }
+
+    // deal in to whichever threat is most likely waiting on the tile
+    let deal_in = |tile : Tile| view.opponent_seats()
+        .filter(|seat| view.opponent(*seat).is_threat())
+        .map(|seat| (view.opponent(seat).tile_danger(tile, &seen_counts), seat))
+        .max();
+    let danger = |tile : Tile| deal_in(tile).map_or(0, |(danger, _)| danger);
+
+    // sorted by shanten then ukeire, so ties go to the more efficient discard. A discard that goes back in shanten is only
+    // worth playing out if it's safer than every discard that doesn't
+    let discards = view.discard_ukeire();
+    let best_shanten = discards[0].shanten;
+    let safest_best_danger = discards.iter().filter(|discard| discard.shanten == best_shanten).map(|discard| danger(discard.discard)).min().unwrap();
+    let candidates : Vec<&DiscardUkeire> = discards.iter()
+        .filter(|discard| discard.shanten == best_shanten || danger(discard.discard) < safest_best_danger)
+        .collect();
+
+    if candidates.len() == 1
+    {   return candidates[0].discard_idx;   }
+
+    let unseen_counts = view.unseen_tile_counts();
+    let mut unseen_tiles : Vec<Tile> = (0..NUM_TILE_KINDS)
+        .flat_map(|kind_idx| std::iter::repeat_n(Tile::from_kind_idx(kind_idx), unseen_counts[kind_idx] as usize))
+        .collect();
+
+    // the wall is dealt from after the other hands, and the seat draws after everyone else has
+    let num_players = view.num_players();
+    let wall_start = view.opponent_seats().map(|seat| view.opponent(seat).num_hand_tiles()).sum::<usize>();
+    let num_draws = settings.search_depth.min(view.tiles_left_in_live_wall() / num_players);
+
+    let mut total_points = vec![0i64; candidates.len()];
+    let mut total_placements = vec![0usize; candidates.len()];
+    for _ in 0..settings.num_rollouts
+    {
+        // every discard plays out the same sample, so they're compared on the same luck
+        unseen_tiles.shuffle(rng);
+        let draws : Vec<Tile> = (0..num_draws).map(|draw| unseen_tiles[wall_start + draw * num_players + num_players - 1]).collect();
+
+        for (candidate_idx, candidate) in candidates.iter().enumerate()
+        {
+            let mut hand = view.hand().to_vec();
+            hand.remove(candidate.discard_idx);
+
+            let points_won = play_out_draws(view, hand, &draws).map_or(0, |basic_points| tsumo_points(view, basic_points));
+            total_points[candidate_idx] += points_won as i64;
+            total_placements[candidate_idx] += placement_after(view, points_won, None);
+        }
+    }
+
+    let estimates = candidates.iter().enumerate().map(|(candidate_idx, candidate)| {
+        let num_rollouts = settings.num_rollouts.max(1) as f64;
+
+        let (deal_in_chance, deal_in_placement) = match deal_in(candidate.discard) {
+            Some((danger, seat)) => ((danger as f64 * DEAL_IN_CHANCE_PER_DANGER).min(1.0), placement_after(view, -DEAL_IN_POINTS, Some(seat))),
+            None => (0.0, 0),
+        };
+
+        DiscardEstimate {
+            discard_idx : candidate.discard_idx,
+            points : (1.0 - deal_in_chance) * total_points[candidate_idx] as f64 / num_rollouts - deal_in_chance * DEAL_IN_POINTS as f64,
+            placement : (1.0 - deal_in_chance) * total_placements[candidate_idx] as f64 / num_rollouts + deal_in_chance * deal_in_placement as f64,
+        }
+    });
+
+    // the first of equally good discards is the most efficient
+    let best = match settings.goal {
+        MonteCarloGoal::Points => estimates.reduce(|best, estimate| if estimate.points > best.points { estimate } else { best }),
+        MonteCarloGoal::Placement => estimates.reduce(|best, estimate| {
+            if estimate.placement < best.placement || (estimate.placement == best.placement && estimate.points > best.points)
+            {   estimate    }
+            else
+            {   best    }
+        }),
+    };
+
+    best.expect("There's always more than one discard to pick from").discard_idx
+}
+
+/// Draws each tile in turn into a hand waiting on a draw. A draw that doesn't lower shanten is discarded straight away, otherwise
+/// the first tile leaving the fewest shanten is. Returns the basic points of the first tsumo with a yaku
+fn play_out_draws(view : &SeatView, mut hand : Vec<Tile>, draws : &[Tile]) -> Option<usize>
+{
+    let num_called_sets = view.player().called_sets.len();
+    let mut counts = tile_counts(&hand);
+    let mut current_shanten = shanten(&counts, num_called_sets);
+
+    for draw in draws
+    {
+        counts[draw.kind_idx()] += 1;
+        let shanten_after_draw = shanten(&counts, num_called_sets);
+
+        if shanten_after_draw >= current_shanten
+        {
+            counts[draw.kind_idx()] -= 1;
+            continue;
+        }
+
+        hand.push(*draw);
+        if shanten_after_draw == COMPLETE_HAND_SHANTEN
+        {
+            let basic_points = view.tsumo_basic_points(&hand);
+            if basic_points > 0
+            {   return Some(basic_points);  }
+
+            // without a yaku the hand stays as it was
+            hand.pop();
+            counts[draw.kind_idx()] -= 1;
+            continue;
+        }
+
+        let hand_kind_idxs : Vec<usize> = (0..NUM_TILE_KINDS).filter(|kind_idx| counts[*kind_idx] > 0).collect();
+        let (discard_kind_idx, shanten_after_discard) = hand_kind_idxs.into_iter()
+            .map(|kind_idx| {
+                counts[kind_idx] -= 1;
+                let shanten_after_discard = shanten(&counts, num_called_sets);
+                counts[kind_idx] += 1;
+                (kind_idx, shanten_after_discard)
+            })
+            .min_by_key(|(_, shanten_after_discard)| *shanten_after_discard)
+            .unwrap();
+
+        counts[discard_kind_idx] -= 1;
+        let discard_idx = hand.iter().rposition(|tile| tile.kind_idx() == discard_kind_idx).unwrap();
+        hand.remove(discard_idx);
+        current_shanten = shanten_after_discard;
+    }
+
+    None
+}
+
+/// everything the seat is paid for a tsumo worth basic_points, including honba and riichi sticks
+fn tsumo_points(view : &SeatView, basic_points : usize) -> i32
+{
+    let basic_points = basic_points as i32;
+    let is_dealer = view.player().seat_wind == SuitVal::East;
+
+    let payments : i32 = view.opponent_seats()
+        .map(|seat| if is_dealer || view.opponent(seat).seat_wind() == SuitVal::East { 2 * basic_points } else { basic_points })
+        .map(utils::round_up_to_100)
+        .sum();
+
+    payments + scoring::HONBA_POINTS * view.honba() as i32 + scoring::RIICHI_STICK_POINTS * view.riichi_sticks() as i32
+}
+
+/// The seat's placement after its points change. A tsumo is paid by everyone equally, which is close enough for placement.
+/// Points lost by dealing in go to the seat they were dealt in to
+fn placement_after(view : &SeatView, point_change : i32, dealt_in_to : Option<usize>) -> usize
+{
+    let num_opponents = view.num_players() as i32 - 1;
+    let points = view.player().points + point_change;
+
+    let num_ahead = view.opponent_seats()
+        .filter(|seat| {
+            let opponent_points = match dealt_in_to {
+                Some(winner) if winner == *seat => view.opponent(*seat).points() - point_change,
+                Some(_) => view.opponent(*seat).points(),
+                None => view.opponent(*seat).points() - point_change / num_opponents,
+            };
+            opponent_points > points
+        })
+        .count();
+
+    num_ahead + 1
+}
+
+
+#[test]
+fn test_monte_carlo_discard()
+{
+    let south = Tile { suit : Suit::Honor, value : SuitVal::South, red : false };
+    let west = Tile { suit : Suit::Honor, value : SuitVal::West, red : false };
+    let north = Tile { suit : Suit::Honor, value : SuitVal::North, red : false };
+
+    // 123m 456m 789p 234s with a lone west and north. Either discard is a tanki wait on the other wind, but a dora west is worth more
+    let mut game = Game::with_seed(RuleSet::tenhou(), 0);
+    game.setup_for_hand();
+    game.players[1].hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::pin_tile(7), Tile::pin_tile(8), Tile::pin_tile(9), Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(4),
+        west, north,
+    ];
+    let dora_indicators_idx = game.dora_indicators_idx();
+    game.tiles[dora_indicators_idx] = south;
+
+    let settings = MonteCarloSettings { search_depth : 8, num_rollouts : 40, goal : MonteCarloGoal::Points };
+    let discard_idx = monte_carlo_discard_idx(&SeatView::new(&game, 1), &settings, &mut game.rng.clone());
+    assert_eq!(game.players[1].hand[discard_idx], north);
+
+    // it plays the same way from the same seed
+    let same_discard_idx = monte_carlo_discard_idx(&SeatView::new(&game, 1), &settings, &mut game.rng.clone());
+    assert_eq!(discard_idx, same_discard_idx);
+
+    // without the dora, an opponent in riichi who discarded the west makes it the better discard
+    game.tiles[dora_indicators_idx] = Tile::pin_tile(9);
+    game.players[2].riichi = true;
+    game.players[2].discard_pile = vec![west];
+    let discard_idx = monte_carlo_discard_idx(&SeatView::new(&game, 1), &settings, &mut game.rng.clone());
+    assert_eq!(game.players[1].hand[discard_idx], west);
+
+    // 234m 345p 456s 77m with a lone 6m, 4p and 5s. Any of those keeps the hand one from tenpai, but they're all dangerous to the
+    // riichi, so it folds with the genbutsu 2m instead
+    game.players[1].hand = vec![
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::pin_tile(3), Tile::pin_tile(4), Tile::pin_tile(5),
+        Tile::sou_tile(4), Tile::sou_tile(5), Tile::sou_tile(6), Tile::man_tile(7), Tile::man_tile(7),
+        Tile::man_tile(6), Tile::pin_tile(4), Tile::sou_tile(5),
+    ];
+    game.players[2].discard_pile = vec![Tile::man_tile(2)];
+    let discard_idx = monte_carlo_discard_idx(&SeatView::new(&game, 1), &settings, &mut game.rng.clone());
+    assert_eq!(game.players[1].hand[discard_idx], Tile::man_tile(2));
+}
@@ -4,22 +4,40 @@ use std::{fmt, slice::Windows, usize::MAX, iter::empty, collections::HashMap, };
 use int_enum::IntEnum;
 use num::pow;
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 
 use crate::mahjong::tile::*;
 use crate::mahjong::Game;
+use crate::mahjong::rules::RuleSet;
 
 use crate::mahjong::tui_output;
 
 use crate::mahjong::scoring;
+use crate::mahjong::scoring::FuComponent;
 
 use crate::mahjong::utils;
+use crate::mahjong::shanten;
+use crate::mahjong::defense;
+use crate::mahjong::strategy::SeatView;
+use crate::mahjong::monte_carlo::MonteCarloSettings;
 
 pub const NUM_PLAYERS    : usize = 4;
+pub const NUM_SANMA_PLAYERS : usize = 3;
 
 
 pub const PLAYER_HAND_SIZE : usize = 14;
-const STARTING_POINTS : i32 = 25000;
+
+/// the defensive AI keeps pushing one tile from tenpai if its hand has this much value from dora, red fives and yakuhai
+const DEFENSIVE_AI_PUSH_VALUE : usize = 2;
+
+/// a closed hand worth this much from dora, red fives and yakuhai isn't opened by the smart calling AI, except to pon yakuhai
+const SMART_CALL_KEEP_CLOSED_VALUE : usize = 3;
+
+/// how many tiles in the hand can be off the way to tanyao or honitsu, and still be discarded on the way
+const SMART_CALL_MAX_OFF_PATH_TILES : usize = 2;
+
+/// four sets and a pair
+const NUM_HAND_SETS_AND_PAIR : usize = 5;
 
 #[derive(Clone, Eq, PartialEq)]
 pub struct Player {
@@ -52,6 +70,9 @@ pub struct Player {
     pub double_riichi : bool,
     pub iipatsu : bool,
 
+    /// norths set aside in sanma. Each one is a dora
+    pub nuki_dora : usize,
+
     pub winning_wait : Option<WaitType>,
     pub ron_or_tsumo : WinningMethod,
 
@@ -138,7 +159,7 @@ impl Default for Player {
             callable_tiles : HashMap::new(),
 
             seat_wind : SuitVal::East,
-            points : STARTING_POINTS,
+            points : RuleSet::default().starting_points,
 
             tenpai : false,
             furiten : false,
@@ -147,12 +168,14 @@ impl Default for Player {
             double_riichi : false,
             iipatsu : false,
 
+            nuki_dora : 0,
+
             is_human : false,
 
             winning_wait : None,
             ron_or_tsumo : WinningMethod::NotWonYet,
 
-            ai_algorithm : AIAlgorithm::SimpleDiscardAlwaysCall,
+            ai_algorithm : AIAlgorithm::DefensiveDiscardSmartCall,
         };
     }
 }
@@ -170,118 +193,230 @@ impl Player {
         self
     }
 
-    pub fn ai_call(&self, discard_tile : Tile) -> Option<CalledSet>
+    /// Discards lone honors, then lone terminals, then other tiles without a pair or neighbor. Otherwise picks at random
+    pub fn simple_discard_idx(&self, rng : &mut impl Rng) -> usize
     {
-        match self.ai_algorithm {
-            AIAlgorithm::DumbAsBricks => return None,
+        // if we decide to keep tiles, we remove them from this vector. This contains the tiles to pick from randomly to discard
+        // at the end of the algorithm
+        let mut hand_copy = self.hand.clone();
 
-            AIAlgorithm::SimpleDiscardAlwaysCall => {
-                let possible_calls = self.callable_tiles.get(&discard_tile).unwrap();
+        // keep honors if there's two, otherwise discard
+        let mut hand_honor_tiles = self.hand.clone();
+        hand_honor_tiles.retain(|tile| tile.suit == Suit::Honor);
 
-                if possible_calls.ron == true {
-                    return Some(CalledSet {
-                        call_type : CallTypes::Ron(possible_calls.ron_set.set_type),
-                        set : possible_calls.ron_set.clone(),
-                    })
-                }
-                else if possible_calls.open_kan {
-                    return Some(CalledSet {
-                        call_type : CallTypes::OpenKan,
-                        set : Set::kan(discard_tile)
-                    })
-                }
-                else if possible_calls.pon {
-                    return Some(CalledSet {
-                        call_type : CallTypes::Pon,
-                        set : Set::triplet(discard_tile)
-                    })
+        for tile in hand_honor_tiles
+        {
+            // check if there's two of them
+            if ! self.callable_tiles.contains_key(&tile)
+            {
+                return self.hand.iter().position(|hand_tile| *hand_tile == tile).unwrap();
+            }
+            else
+            {
+                hand_copy.retain(|hand_tile| *hand_tile != tile);
+            }
+        }
+
+        // keep terminals if there's two of them, or if they have the adjacent sequence number. Otherwise discard
+        let mut hand_terminal_tiles = self.hand.clone();
+        hand_terminal_tiles.retain(|tile| tile.value == SuitVal::One || tile.value == SuitVal::Nine);
+
+        for tile in hand_terminal_tiles
+        {
+            // check if there's two of them
+            if ! self.callable_tiles.contains_key(&tile)
+            {
+                // check if the hand contains an adjacent tile (if so, then chii-ing is an option)
+                if ! numbered_tile_has_a_neighbor(tile, &self.hand)
+                {
+                    return self.hand.iter().position(|hand_tile| *hand_tile == tile).unwrap();
                 }
-                else if possible_calls.chii {
-                    let chiiable_sets = get_callable_chii_combinations_with_tile(&self.hand, discard_tile);
-                    return Some(chiiable_sets[0].clone());
+            }
+
+            hand_copy.retain(|hand_tile| *hand_tile != tile);
+        }
+
+        // remove non-terminal number tiles without a pair or neighbor
+        for tile in hand_copy.clone()
+        {
+            if ! self.callable_tiles.contains_key(&tile)
+            {
+                // due to previous logic, ALL tiles within hand_copy at this point aren't terminals
+                if ! numbered_tile_has_a_neighbor(tile, &self.hand)
+                {
+                    return self.hand.iter().position(|hand_tile| *hand_tile == tile).unwrap();
                 }
                 else
                 {
-                    return None;
+                    hand_copy.retain(|hand_tile| *hand_tile != tile);
                 }
             }
         }
+
+        // discard any remaining tiles which don't have pair or neighbor
+        if ! hand_copy.is_empty()
+        {
+            return self.hand.iter().position(|hand_tile| *hand_tile == hand_copy[0]).unwrap();
+        }
+
+        // we must remove a tile with a pair or neighbor now
+        // TODO: Don't discard numbers part of existing sets
+        rng.gen_range(0..self.hand.len())
     }
 
-    pub fn ai_discard(&self) -> usize
+    /// the discard leaving the hand fewest tiles from tenpai, then with the most tiles left that would improve it.
+    /// Ties keep whichever tiles are worth the most
+    pub fn efficient_discard_idx(&self, view : &SeatView) -> usize
     {
-        match self.ai_algorithm {
-            AIAlgorithm::DumbAsBricks => return 0,
-
-            AIAlgorithm::SimpleDiscardAlwaysCall => {
-                // if we decide to keep tiles, we remove them from this vector. This contains the tiles to pick from randomly to discard
-                // at the end of the algorithm
-                let mut hand_copy = self.hand.clone();
+        let discards = view.discard_ukeire();
+        let best_discard = &discards[0];
+
+        discards.iter()
+            .take_while(|discard| discard.shanten == best_discard.shanten && discard.num_accepted() == best_discard.num_accepted())
+            .min_by_key(|discard| self.tile_value(view, discard.discard))
+            .unwrap()
+            .discard_idx
+    }
 
-                // keep honors if there's two, otherwise discard
-                let mut hand_honor_tiles = self.hand.clone();
-                hand_honor_tiles.retain(|tile| tile.suit == Suit::Honor);
+    /// Plays for efficiency until an opponent is in riichi or looks tenpai. Then a tenpai hand, or a valuable hand one
+    /// tile from tenpai against a single opponent, pushes with its safest discard that doesn't go back in shanten.
+    /// Anything else folds with the safest tile in the hand
+    pub fn defensive_discard_idx(&self, view : &SeatView) -> usize
+    {
+        let threats : Vec<_> = view.opponent_seats()
+            .map(|seat| view.opponent(seat))
+            .filter(|opponent| opponent.is_threat())
+            .collect();
 
-                for tile in hand_honor_tiles
-                {
-                    // check if there's two of them
-                    if ! self.callable_tiles.contains_key(&tile)
-                    {
-                        return self.hand.iter().position(|hand_tile| *hand_tile == tile).unwrap();
-                    }
-                    else
-                    {
-                        hand_copy.retain(|hand_tile| *hand_tile != tile);
-                    }
-                }
+        if threats.is_empty()
+        {
+            return self.efficient_discard_idx(view);
+        }
 
-                // keep terminals if there's two of them, or if they have the adjacent sequence number. Otherwise discard
-                let mut hand_terminal_tiles = self.hand.clone();
-                hand_terminal_tiles.retain(|tile| tile.value == SuitVal::One || tile.value == SuitVal::Nine);
+        let mut seen_counts = view.visible_tile_counts();
+        for tile in &self.hand
+        {   seen_counts[tile.kind_idx()] += 1;   }
 
-                for tile in hand_terminal_tiles
-                {
-                    // check if there's two of them
-                    if ! self.callable_tiles.contains_key(&tile)
-                    {
-                        // check if the hand contains an adjacent tile (if so, then chii-ing is an option)
-                        if ! numbered_tile_has_a_neighbor(tile, &self.hand)
-                        {
-                            return self.hand.iter().position(|hand_tile| *hand_tile == tile).unwrap();
-                        }
-                    }
+        let danger = |tile : Tile| -> u32 {
+            threats.iter().map(|threat| threat.tile_danger(tile, &seen_counts)).sum()
+        };
 
-                    hand_copy.retain(|hand_tile| *hand_tile != tile);
-                }
+        let discards = view.discard_ukeire();
+        let best_shanten = discards[0].shanten;
+        let hand_value : usize = self.hand.iter().map(|tile| self.tile_value(view, *tile)).sum();
+        let push = best_shanten == 0 || (best_shanten == 1 && hand_value >= DEFENSIVE_AI_PUSH_VALUE && threats.len() == 1);
+
+        // discards are sorted by efficiency, so the safest tiles are still picked in order of efficiency
+        discards.iter()
+            .filter(|discard| ! push || discard.shanten == best_shanten)
+            .min_by_key(|discard| danger(discard.discard))
+            .unwrap()
+            .discard_idx
+    }
 
-                // remove non-terminal number tiles without a pair or neighbor
-                for tile in hand_copy.clone()
-                {
-                    if ! self.callable_tiles.contains_key(&tile)
-                    {
-                        // due to previous logic, ALL tiles within hand_copy at this point aren't terminals
-                        if ! numbered_tile_has_a_neighbor(tile, &self.hand)
-                        {
-                            return self.hand.iter().position(|hand_tile| *hand_tile == tile).unwrap();
-                        }
-                        else
-                        {
-                            hand_copy.retain(|hand_tile| *hand_tile != tile);
-                        }
-                    }
-                }
+    /// Rons when the win has a yaku, unless the win ends the game with the player still in last place.
+    /// Other calls are only made when they lower shanten, still leave the hand a yaku to win with, and don't open a
+    /// valuable closed hand. Nothing but ron is called while folding to a threat
+    pub fn smart_call(&self, view : &SeatView, discard_tile : Tile) -> Option<CalledSet>
+    {
+        let possible_calls = self.callable_tiles.get(&discard_tile)?;
 
-                // discard any remaining tiles which don't have pair or neighbor
-                if hand_copy.len() != 0
-                {
-                    return self.hand.iter().position(|hand_tile| *hand_tile == hand_copy[0]).unwrap();
-                }
+        if possible_calls.ron
+        {
+            let ron_call = CalledSet {
+                call_type : CallTypes::Ron(possible_calls.ron_set.set_type),
+                set : possible_calls.ron_set.clone(),
+            };
 
-                // we must remove a tile with a pair or neighbor now
-                // TODO: Don't discard numbers part of existing sets
-                return rand::thread_rng().gen_range(0..self.hand.len());
-            }
+            if self.wants_to_ron(view, discard_tile, &ron_call)
+            {   return Some(ron_call);  }
         }
+
+        let num_players = view.num_players();
+        let can_chii = possible_calls.chii && view.current_seat() == (view.seat() + num_players - 1) % num_players && ! view.is_sanma();
+
+        let mut candidate_calls = vec![];
+        if possible_calls.pon
+        {   candidate_calls.push(CalledSet { call_type : CallTypes::Pon, set : Set::triplet(discard_tile) });    }
+        if can_chii
+        {   candidate_calls.extend(get_callable_chii_combinations_with_tile(&self.hand, discard_tile));   }
+
+        let shanten_before = self.shanten();
+        let is_folding = view.opponent_seats().any(|seat| view.opponent(seat).is_threat());
+        let hand_value : usize = self.hand.iter().map(|tile| self.tile_value(view, *tile)).sum();
+
+        candidate_calls.into_iter()
+            .filter_map(|call| {
+                let is_yakuhai_pon = call.call_type == CallTypes::Pon && view.is_yakuhai_tile(discard_tile);
+
+                let mut after_call = self.clone();
+                after_call.open_tiles_with_call(discard_tile, call.clone());
+                // the hand still discards after calling, so it's at best tenpai
+                let shanten_after = after_call.shanten().max(0);
+
+                let worth_calling = shanten_after < shanten_before
+                    && (shanten_after <= 0 || ! is_folding)
+                    && after_call.has_open_yaku_path(view)
+                    && (is_yakuhai_pon || ! self.hand_is_closed() || hand_value < SMART_CALL_KEEP_CLOSED_VALUE);
+
+                worth_calling.then_some((shanten_after, call))
+            })
+            .min_by_key(|(shanten_after, _)| *shanten_after)
+            .map(|(_, call)| call)
+    }
+
+    /// a ron needs a yaku. A win that ends the game with the player still in last place is passed on
+    fn wants_to_ron(&self, view : &SeatView, ron_tile : Tile, ron_call : &CalledSet) -> bool
+    {
+        let basic_points = view.ron_basic_points(ron_tile, ron_call) as i32;
+        if basic_points == 0
+        {   return false;   }
+
+        let is_dealer = self.seat_wind == SuitVal::East;
+        let payment = utils::round_up_to_100(basic_points * if is_dealer { 6 } else { 4 }) + scoring::HONBA_POINTS * view.honba() as i32;
+
+        let mut points_after : Vec<i32> = (0..view.num_players())
+            .map(|seat| if seat == view.seat() { self.points } else { view.opponent(seat).points() })
+            .collect();
+        points_after[view.seat()] += payment + scoring::RIICHI_STICK_POINTS * view.riichi_sticks() as i32;
+        points_after[view.current_seat()] -= payment;
+
+        ! view.game_ends_after_win(&points_after) || points_after.iter().any(|points| *points < points_after[view.seat()])
+    }
+
+    /// true if an open hand can still win with a yaku: a yakuhai triplet or pair, tanyao, honitsu or toitoi
+    fn has_open_yaku_path(&self, view : &SeatView) -> bool
+    {
+        let called_tiles = || self.called_sets.iter().flat_map(|called_set| called_set.set.tiles.iter());
+        let counts = tile_counts(&self.hand);
+
+        let has_yakuhai = self.called_sets.iter().any(|called_set| called_set.set.set_type != SetType::Sequence && view.is_yakuhai_tile(called_set.set.tiles[0]))
+            || self.hand.iter().any(|tile| counts[tile.kind_idx()] >= 2 && view.is_yakuhai_tile(*tile));
+
+        let has_tanyao = view.rules().kuitan
+            && called_tiles().all(|tile| ! tile.is_honor_or_terminal())
+            && self.hand.iter().filter(|tile| tile.is_honor_or_terminal()).count() <= SMART_CALL_MAX_OFF_PATH_TILES;
+
+        let has_honitsu = [Suit::Man, Suit::Pin, Suit::Sou].iter().any(|suit| {
+            called_tiles().all(|tile| tile.suit == *suit || tile.suit == Suit::Honor)
+            && self.hand.iter().filter(|tile| tile.suit != *suit && tile.suit != Suit::Honor).count() <= SMART_CALL_MAX_OFF_PATH_TILES
+        });
+
+        let num_hand_pairs = counts.iter().filter(|count| **count >= 2).count();
+        let has_toitoi = self.called_sets.iter().all(|called_set| called_set.set.set_type != SetType::Sequence)
+            && num_hand_pairs + self.called_sets.len() >= NUM_HAND_SETS_AND_PAIR - 1;
+
+        has_yakuhai || has_tanyao || has_honitsu || has_toitoi
+    }
+
+    /// how much a tile adds to the hand's value, apart from making sets. One for each dora it is, one for a red five,
+    /// and one if it's part of a yakuhai pair
+    pub fn tile_value(&self, view : &SeatView, tile : Tile) -> usize
+    {
+        let num_dora = view.dora().iter().filter(|dora| **dora == tile).count();
+        let is_yakuhai_pair = view.is_yakuhai_tile(tile) && self.hand.iter().filter(|hand_tile| **hand_tile == tile).count() >= 2;
+
+        num_dora + tile.red as usize + is_yakuhai_pair as usize
     }
 
     pub fn dump_player_state(&self)
@@ -453,9 +588,11 @@ impl Player {
     // }
 
     /// rotates the players wind counter clockwise (against intutition)
-    pub fn rotate_wind(&mut self)
+    /// there's no north seat in sanma, so the dealer becomes west
+    pub fn rotate_wind(&mut self, num_players : usize)
     {
         self.seat_wind = match self.seat_wind{
+            SuitVal::East if num_players == NUM_SANMA_PLAYERS => SuitVal::West,
             SuitVal::East => SuitVal::North,
             SuitVal::North => SuitVal::West,
             SuitVal::West => SuitVal::South,
@@ -587,220 +724,235 @@ impl Player {
         return false;
    }
 
-    // excludes kazoe (yakuman from enough han)
-    fn hand_yakuman_in_basic_points(&self, game : &Game) -> usize
+    /// every tile in the player's hand and called sets
+    fn all_tiles(&self) -> impl Iterator<Item = &Tile>
     {
-        let mut basic_points : usize = 0;
+        self.hand.iter().chain(self.called_sets.iter().flat_map(|called_set| called_set.set.tiles.iter()))
+    }
 
-        for yakuman in scoring::YAKUMAN_FUNCS
-        { // 8000 basic points per yakuman. Some functions can return double yakuman (a value of 2)
-            basic_points += 8000 * yakuman(self, game);
-        }
+    /// one han for each tile matching a dora. A tile counts once for every indicator pointing at it
+    fn num_tiles_indicated_by(&self, indicators : &[Tile], game : &Game) -> usize
+    {
+        indicators.iter()
+            .map(|indicator| game.dora_from_indicator(*indicator))
+            .map(|dora| self.all_tiles().filter(|tile| **tile == dora).count() + if dora == NORTH_TILE { self.nuki_dora } else { 0 })
+            .sum()
+    }
 
-        return basic_points;
+    pub fn is_in_riichi(&self) -> bool
+    {
+        self.riichi || self.double_riichi
     }
 
-    fn hand_yaku_in_han(&self) -> usize
+    /// true if one more tile would complete the hand. The tiles don't need to be sorted
+    pub fn tiles_are_tenpai(tiles : &[Tile]) -> bool
     {
-        0
+        ! find_waits(tiles).is_empty()
     }
 
-    fn hand_dora_in_han(&self) -> usize
+    /// indexes of the tiles in the hand which leave the hand in tenpai when discarded
+    pub fn tenpai_discard_idxs(&self) -> Vec<usize>
     {
-        0
+        (0..self.hand.len()).filter(|discard_idx| {
+            let mut tiles_after_discard = self.hand.clone();
+            tiles_after_discard.remove(*discard_idx);
+
+            Player::tiles_are_tenpai(&tiles_after_discard)
+        }).collect()
+    }
+
+    /// number of tiles the hand needs to change to be tenpai. 0 is tenpai and -1 is a complete hand
+    pub fn shanten(&self) -> i32
+    {
+        shanten::shanten(&tile_counts(&self.hand), self.called_sets.len())
+    }
+
+    /// true at an exhaustive draw when the player only discarded terminals and honors, and none of them were called
+    pub fn is_nagashi_mangan(&self) -> bool
+    {
+        ! self.discard_pile.is_empty()
+        && self.tiles_others_called.is_empty()
+        && self.discard_pile.iter().all(|tile| tile.is_honor_or_terminal())
+    }
+
+    /// number of different terminal and honor tiles in the hand. Nine or more on the first draw allows kyuushu kyuuhai
+    pub fn num_unique_terminals_and_honors(&self) -> usize
+    {
+        let mut terminals_and_honors : Vec<Tile> = self.hand.iter()
+            .filter(|tile| tile.is_honor_or_terminal())
+            .copied()
+            .collect();
+        terminals_and_honors.sort();
+        terminals_and_honors.dedup();
+
+        terminals_and_honors.len()
+    }
+
+    /// tiles the player holds all four of in their hand, which could be declared as a closed kan
+    pub fn closed_kan_tiles(&self) -> Vec<Tile>
+    {
+        let mut kan_tiles : Vec<Tile> = self.hand.iter()
+            .filter(|tile| self.hand.iter().filter(|hand_tile| hand_tile == tile).count() == 4)
+            .copied()
+            .collect();
+        kan_tiles.sort();
+        kan_tiles.dedup();
+
+        kan_tiles
+    }
+
+    /// tiles in the player's hand which could be added to one of their pons to make a kan
+    pub fn added_kan_tiles(&self) -> Vec<Tile>
+    {
+        let mut kan_tiles : Vec<Tile> = self.hand.iter()
+            .filter(|tile| self.callable_tiles.get(tile).is_some_and(|calls| calls.added_kan))
+            .copied()
+            .collect();
+        kan_tiles.sort();
+        kan_tiles.dedup();
+
+        kan_tiles
     }
 
+    /// each nuki dora is worth one han on top of any dora it's indicated as
+    pub fn hand_dora_in_han(&self, game : &Game) -> usize
+    {
+        self.num_tiles_indicated_by(&game.dora_indicators(), game) + self.nuki_dora
+    }
+
+    /// ura dora only count for players who won after declaring riichi
+    pub fn hand_ura_dora_in_han(&self, game : &Game) -> usize
+    {
+        if self.riichi || self.double_riichi
+        {   self.num_tiles_indicated_by(&game.ura_dora_indicators(), game)   }
+        else
+        {   0   }
+    }
+
+    /// one han for each red five
+    pub fn hand_aka_dora_in_han(&self) -> usize
+    {
+        self.all_tiles().filter(|tile| tile.red).count()
+    }
+
+    /// returns the fu of the best interpretation of a winning hand, or 0 if the hand isn't a winning hand
     pub fn hand_fu(&self, game : &Game, round_up : bool) -> usize
     {
-        // chiitoitsu (seven pairs) is always 25 fu
-        if scoring::yaku_chiitoitsu(&self, game) != 0
-        {
-            return 25;
-        }
-
-        let mut fu = 20;
-
-        // add fu for revealed sets (which include closed kans)
-        for set in &self.called_sets {
-            let mut added_fu =  match set.call_type {
-                                        CallTypes::ClosedKan => 16,
-                                        CallTypes::OpenKan => 8,
-                                        CallTypes::Pon => 2,
-                                        CallTypes::Ron(set_type) => {
-                                            match set_type {
-                                                SetType::Kan => 8,
-                                                SetType::Triplet => 2,
-                                                _ => 0
-                                            }
-                                        }
-                                        _ => 0,
-                                    };
-
-            if set.set.has_honor_or_terminal()
-            {
-                added_fu *= 2;
-            }
+        let mut max_fu = 0;
 
-            fu += added_fu;
+        for interpretation in self.winning_hand_interpretations()
+        {
+            max_fu = std::cmp::max(max_fu, self.interpretation_fu(game, &interpretation, round_up));
         }
 
-        // add fu for closed triplets
-        let mut i = 0;
-        while i < self.hand.len() - 2
+        max_fu
+    }
+
+    /// returns the fu for one interpretation of a winning hand
+    pub fn interpretation_fu(&self, game : &Game, hand : &HandInterpretation, round_up : bool) -> usize
+    {
+        let fu = self.interpretation_fu_components(game, hand).iter().map(|(_, fu)| fu).sum();
+
+        if round_up
+        {   scoring::round_up_fu(fu)  }
+        else
+        {   fu  }
+    }
+
+    /// returns each source of fu for one interpretation of a winning hand, before rounding
+    pub fn interpretation_fu_components(&self, game : &Game, hand : &HandInterpretation) -> Vec<(FuComponent, usize)>
+    {
+        // chiitoitsu (seven pairs) is always 25 fu
+        if scoring::yaku_chiitoitsu(self, game, hand) != 0
         {
-            let curr : Tile = self.hand[i];
-            let next : Tile = self.hand[i + 1];
-            let nextnext : Tile = self.hand[i + 2];
+            return vec![(FuComponent::Chiitoitsu, 25)];
+        }
 
-            if curr.suit == next.suit && curr.value == next.value
-            {
-                if next.suit == nextnext.suit && next.value == nextnext.value
-                {
-                    let mut added_fu = 4;
+        let is_pinfu = scoring::yaku_pinfu(self, game, hand) != 0;
 
-                    if next.suit == Suit::Honor || next.value == SuitVal::One || next.value == SuitVal::Nine
-                    {
-                        added_fu *= 2;
-                    }
+        let mut components = vec![(FuComponent::Base, 20)];
 
-                    fu += added_fu;
-                    i += 3;
-                    continue;
-                }
-                else
-                {
-                    i += 2;
-                    continue;
-                }
-            }
-            else
-            {
-                i += 1;
-                continue;
-            }
+        // add fu for triplets and kans. Concealed sets are worth double
+        for set in &hand.concealed_sets {
+            let fu = match set.set_type {
+                SetType::Kan => 16,
+                SetType::Triplet => 4,
+                _ => 0,
+            } * if set.has_honor_or_terminal() { 2 } else { 1 };
+
+            if fu > 0
+            {   components.push((FuComponent::ConcealedSet(set.clone()), fu));   }
         }
 
-        // add fu for a pair of honor tiles which would count as yaku
-        let winning_pair = self.get_one_pair();
-        if let None = winning_pair
-        {
-            // In riichi mahjong there is ALWAYS a winning pair. Return 0 fu since the hand isn't a winning hand
-            return 0;
+        for set in &hand.open_sets {
+            let fu = match set.set_type {
+                SetType::Kan => 8,
+                SetType::Triplet => 2,
+                _ => 0,
+            } * if set.has_honor_or_terminal() { 2 } else { 1 };
+
+            if fu > 0
+            {   components.push((FuComponent::OpenSet(set.clone()), fu));   }
         }
-        else if let Some(winning_pair) = winning_pair
+
+        // add fu for a pair of honor tiles which would count as yaku
+        if let Some(pair) = hand.pair()
         {
-            if winning_pair.tiles[0].suit == Suit::Honor
+            let mut fu = 0;
+            if pair.tiles[0].is_dragon()
             {
-                fu +=   match winning_pair.tiles[0].value {
-                            SuitVal::Red => 2,
-                            SuitVal::White => 2,
-                            SuitVal::Green => 2,
-                            _ => 0,
-                        };
-
-                if winning_pair.tiles[0].value == game.round_wind
+                fu += 2;
+            }
+            else if pair.tiles[0].suit == Suit::Honor
+            {
+                if pair.tiles[0].value == game.round_wind
                 {
                     fu += 2;
                 }
 
-                if winning_pair.tiles[0].value == self.seat_wind
+                if pair.tiles[0].value == self.seat_wind
                 {
                     fu += 2;
                 }
             }
+
+            if fu > 0
+            {   components.push((FuComponent::YakuhaiPair(pair.clone()), fu));   }
         }
 
         // add fu for winning wait
-        fu +=   match self.winning_wait {
-                    Some(WaitType::Ryanmen) => 0,
-                    Some(WaitType::Kanchan) => 2,
-                    Some(WaitType::Penchan) => 2,
-                    Some(WaitType::Tanki)   => 2,
-                    Some(WaitType::Shanpon) => 0,
-                    None => panic!("Attempted to score fu for non winning player"),
-                };
+        match hand.wait {
+            WaitType::Kanchan | WaitType::Penchan | WaitType::Tanki => components.push((FuComponent::Wait(hand.wait), 2)),
+            WaitType::Ryanmen | WaitType::Shanpon | WaitType::KokushiThirteenSided | WaitType::ChuurenNineSided => (),
+        }
 
         // add fu for ron or tsumo
-        fu +=   match self.ron_or_tsumo {
-                    WinningMethod::NotWonYet => 0,
-                    WinningMethod::Ron(_) => {
-                        if self.hand_is_closed()
-                        { 10 }
-                        else
-                        { 0 }
-                    },
-                    WinningMethod::Tsumo => {
-                        if scoring::yaku_pinfu(&self, game) != 0
-                        { 0 }
-                        else
-                        { 2 }
-                    },
-                };
-
-
-        // for pinfu
-        if fu == 20
-        {
-            fu = 30;
+        match self.ron_or_tsumo {
+            WinningMethod::NotWonYet => (),
+            WinningMethod::Ron(_) => {
+                if self.hand_is_closed()
+                {   components.push((FuComponent::MenzenRon, 10));  }
+            },
+            WinningMethod::Tsumo => {
+                if ! is_pinfu
+                {   components.push((FuComponent::Tsumo, 2));   }
+            },
         }
 
-        // round up to 10
-        if round_up{
-            let last_dig = fu % 10;
-            if last_dig != 0
-            {
-                fu += 10 - last_dig;
-            }
+        // a pinfu tsumo is the only hand which stays at 20 fu. An open hand without any fu is raised to 30
+        let fu : usize = components.iter().map(|(_, fu)| fu).sum();
+        if fu == 20 && ! (is_pinfu && self.ron_or_tsumo == WinningMethod::Tsumo)
+        {
+            components.push((FuComponent::OpenPinfu, 10));
         }
 
-        return fu;
+        components
     }
 
     // way more complex than it should be imo. Way to go Japanese!
     pub fn score_hand_basic_points(&self, game : &Game) -> usize
     {
-        // double yakuman, come on!
-        let yakuman_pts = self.hand_yakuman_in_basic_points(game);
-        if yakuman_pts > 0
-        {
-            return yakuman_pts;
-        }
-
-
-        let mut han = self.hand_yaku_in_han();
-        // at least one yaku or yakuman is required to have a valid scoring hand
-        if han == 0 {
-            return 0;
-        }
-
-        han += self.hand_dora_in_han();
-
-        // don't score fu if 5 han or above
-        if han >= 5
-        {
-            if han == 5
-            {   return 2000;    }
-            else if han <= 7
-            {   return 3000;    }
-            else if han <= 10
-            {   return 4000;    }
-            else if han <= 12
-            {   return 6000;    }
-            else // hand counted as yakuman if there's enough han
-            {   return 8000;   }
-
-        }
-        else
-        {
-            let mut basic_points = self.hand_fu(game, true) * pow(2, 2 + han);
-
-            // if han and fu reach over 2000 points, it's considered a 2000 point mangan
-            if basic_points > 2000
-            {   return 2000;            }
-            else
-            {   return basic_points;    }
-        }
-
+        scoring::evaluate_hand(self, game).basic_points
     }
 
     pub fn set_hand(&mut self, hand : Vec<Tile>) ->  &mut Player
@@ -982,38 +1134,27 @@ impl Player {
             return true;
         }
 
-        // Sometimes there's more than 1 possible hand close to winning
-        // If there's more than 1 Vec<Set> returned though, they're gauranteed to be
-        // the same amount of steps away from winning
-        let most_hand_sets : Vec<Vec<Set>> = self.find_best_hands(&self.hand[..]);
+        // a hand holding a drawn tile is either complete, or keeps the waits it had before the draw
+        if self.hand.len() % 3 == 2
+        {
+            return is_complete_hand(&self.hand);
+        }
+
+        let waits = find_waits(&self.hand);
 
-        if most_hand_sets.len() == 0
+        if waits.is_empty()
         {
             self.tenpai = false;
             self.furiten = false;
-            return false;
+            self.winning_call_tiles.clear();
         }
         else
         {
-            let num_sets_in_hand = most_hand_sets[0].len();
-
-            if num_sets_in_hand + self.called_sets.len() == 5
-            {
-                return true;
-            }
-            else if num_sets_in_hand + self.called_sets.len() == 4
-            {
-                self.set_tenpai_true_and_update_winning_tiles(most_hand_sets);
-                self.check_and_set_furiten();
-                return false;
-            }
-            else {
-                self.tenpai = false;
-                self.furiten = false;
-                return false;
-            }
+            self.set_tenpai_true_and_update_winning_tiles(waits);
+            self.check_and_set_furiten();
         }
 
+        false
     }
 }
 
@@ -1040,113 +1181,31 @@ impl Player
         return;
     }
 
-    /// One of the most important functions. Determines the best possible groupings of tiles
-    /// within a players hands. This function only returns hands which are the smallest number
-    /// of "Sets" or tile groups away from winning. There can be multiple hands the same steps
-    /// away though (hence the Vec<Vec<>>).
-    fn find_best_hands(&self, remaining_tiles : &[Tile]) -> Vec<Vec<Set>>
-    {
-        let mut best_hands : Vec<Vec<Set>> = vec![];
-
-        for i in 0..remaining_tiles.len()
-        {
-            // try to find a set match for this tile. If there is one, then recurse further
-            // if not, then just iterate to the next option
-            let total_hand = &remaining_tiles[i..]; // TODO: Remove name from this variable, and revamp how these variables are used
-            let this_tile = remaining_tiles[i];
-            let hand_without_this = &remaining_tiles[(i+1)..];
-
-            let sets_possible_with_this_tile = find_possible_sets_with_tile(this_tile, hand_without_this);
-
-            if sets_possible_with_this_tile.len() == 0
-            {
-                continue;
-            }
-            else {
-
-                for set in &sets_possible_with_this_tile
-                {
-                    let mut hand_with_set_tiles_removed : Vec<Tile> = total_hand.to_vec();
-
-                    // remove set tiles from hand
-                    for tile in &set.tiles {
-                        let pos = hand_with_set_tiles_removed.iter().position(
-                            |check_tile| *check_tile == *tile
-                        ).expect("Attempted to remove a tile from a set which should be in the hand");
-
-                        hand_with_set_tiles_removed.remove(pos);
-                    }
-
-                    let mut a_set_removed_best_hands = self.find_best_hands(&hand_with_set_tiles_removed);
-
-                    if a_set_removed_best_hands.len() > 0
-                    {
-                        for mut hand in a_set_removed_best_hands
-                        {
-                            hand.push(set.clone());
-/*
-                            // remove hands which contain more than one pair TODO: Check for 7 pairs yakuman
-                            if hand.iter().filter(|set| set.set_type == SetType::Pair).count() <= 1
-                            {
-                                best_hands.push(hand);
-                            }
-*/
-                            // if a hand contains more than one pair, we'll add the pairs towards the end later TODO: Check for 7 pairs yakuman
-                            // so remove them and use the pair closest to left. Otherwise we miss the leftmost pair
-                            while  hand.iter().filter(|set| set.set_type == SetType::Pair).count() > 1
-                            {
-                                let remove_idx = hand.iter().position(|set| set.set_type == SetType::Pair).unwrap();
-                                hand.remove(remove_idx);
-                            }
-
-                            best_hands.push(hand);
-//                            */
-                        }
-                    }
-                    else {
-                        best_hands.push(vec![set.clone()]);
-                    }
-                }
-            }
-        }
-
-        // remove hands if they have less possible sets than the maximum
-        let mut max_hand_len = 0;
-        for hand in &best_hands {
-            if hand.len() > max_hand_len
-            {
-                max_hand_len = hand.len();
-            }
-        }
-
-        best_hands.retain(|sets| sets.len() == max_hand_len);
-
-        return best_hands;
-    }
-
     fn check_furiten(&self) -> ()
     {
         unimplemented!();
     }
 
-    pub fn set_tenpai_true_and_update_winning_tiles(&mut self, best_hands : Vec<Vec<Set>>) -> ()
+    pub fn set_tenpai_true_and_update_winning_tiles(&mut self, waits : Vec<Wait>)
     {
         self.tenpai = true;
 //         self.furiten = self.check_furiten();
         // TODO: Prompt for riichi or double riichi
 
-        let winning_calls = get_winning_tiles_from_tenpai_hand(&self.hand, best_hands);
-
         // TODO: Remove this awful logic, and only append new winning tiles probably. Might have to leave it though, idk
         self.winning_call_tiles.clear();
 
-        for (tile, set) in winning_calls
+        // a tile can complete the hand more than one way. The ron is called with the first, and scoring finds the best reading
+        for wait in waits
         {
-            self.winning_call_tiles.push(tile);
+            if self.winning_call_tiles.contains(&wait.winning_tile)
+            {   continue;   }
+
+            self.winning_call_tiles.push(wait.winning_tile);
 
-            let entry = self.callable_tiles.entry(tile).or_insert( Calls::default() );
+            let entry = self.callable_tiles.entry(wait.winning_tile).or_default();
             entry.ron = true;
-            entry.ron_set = set;
+            entry.ron_set = wait.ron_set();
         }
     }
 
@@ -1168,11 +1227,14 @@ impl Player
                 }
             },
             SetType::Pair => {
+                // kokushi rons on just the discarded tile, so there's nothing to take from the hand
+                for i in 0..(called_set.set.tiles.len() - 1) {
                     let remove_idx = self.hand.iter().position(
                         |hand_tile| *hand_tile == discarded_tile
                     ).unwrap();
 
                     self.hand.remove(remove_idx);
+                }
             }
             SetType::Sequence => {
                 // Remove tiles from sequence. One tile will be missing, and We'll just skip it
@@ -1209,69 +1271,84 @@ impl Player
         }
     }
 
+    /// every call the player could make on a discarded tile
+    pub fn possible_calls_on_discard(self_index : usize, discarded_tile : Tile, game : &Game) -> Vec<CalledSet>
+    {
+        let mut all_possible_calls : Vec<CalledSet> = vec![];
+
+        let player_in_riichi = game.players[self_index].is_in_riichi();
+        let mut possible_calls = game.players[self_index].callable_tiles.get(&discarded_tile).cloned().unwrap_or_default();
+
+        // a player in riichi can only call ron
+        if player_in_riichi
+        {
+            possible_calls = Calls { ron : possible_calls.ron, ron_set : possible_calls.ron_set, ..Calls::default() };
+        }
+
+        if possible_calls.pon
+        {
+            all_possible_calls.push(
+                    CalledSet { set : Set {
+                        set_type : SetType::Triplet,
+                        tiles : vec![discarded_tile ; 3],
+                    },
+                    call_type : CallTypes::Pon,
+                }
+            );
+        }
+        if possible_calls.open_kan && game.can_declare_kan()
+        {
+            all_possible_calls.push(
+                CalledSet {
+                    set : Set {
+                        set_type : SetType::Kan, // closed kan happens at discard
+                        tiles : vec![discarded_tile ; 4],
+                    },
+                call_type : CallTypes::OpenKan,
+                });
+        }
+        if possible_calls.closed_kan && game.curr_player_idx == self_index
+        {
+            all_possible_calls.push(
+                CalledSet {
+                    set : Set {
+                        set_type : SetType::Kan, // closed kan happens at discard
+                        tiles : vec![discarded_tile ; 4],
+                    },
+                call_type : CallTypes::ClosedKan,
+                });
+        }
+        // added kan is only possible during drawing. Not for calling on other player's discarded tiles
+        if possible_calls.chii && (game.curr_player_idx + 1) % game.num_players() == self_index && ! game.is_sanma()
+        {
+            let mut chiiable_sets = get_callable_chii_combinations_with_tile(&game.players[self_index].hand, discarded_tile);
+            all_possible_calls.append(&mut chiiable_sets);
+        }
+        if possible_calls.ron
+        {
+            all_possible_calls.push(
+                CalledSet {
+                    set : possible_calls.ron_set.clone(),
+                    call_type: CallTypes::Ron(possible_calls.ron_set.set_type)
+                });
+        }
+
+        all_possible_calls
+    }
+
     pub fn choose_whether_to_call(self_index : usize, discarded_tile : Tile, game : &mut Game) -> Option<CalledSet>
     {
         println!("\n\n\n\n\n\n\n\ncalling choice called for {} on {}\n\n\n\n\n\n\n\n", self_index, discarded_tile);
 
         // TODO: DONT FORGET TO SHUFFLE AND TO UPDATE CALLABLE TILES ON THIS PLAYER IF A CALL IS ACTUALLY MADE
+        let mut all_possible_calls = Player::possible_calls_on_discard(self_index, discarded_tile, game);
+
         if ! game.players[self_index].is_human
         {
-            return game.players[self_index].ai_call(discarded_tile);
+            game.ai_choose_call(self_index, discarded_tile, &all_possible_calls)
         }
         else
         {
-            let mut all_possible_calls : Vec<CalledSet> = vec![];
-
-            let possible_calls = game.players[self_index].callable_tiles.entry(discarded_tile).or_default();
-
-            if possible_calls.pon
-            {
-                all_possible_calls.push(
-                        CalledSet { set : Set {
-                            set_type : SetType::Triplet,
-                            tiles : vec![discarded_tile ; 3],
-                        },
-                        call_type : CallTypes::Pon,
-                    }
-                );
-            }
-            if possible_calls.open_kan
-            {
-                all_possible_calls.push(
-                    CalledSet {
-                        set : Set {
-                            set_type : SetType::Kan, // closed kan happens at discard
-                            tiles : vec![discarded_tile ; 4],
-                        },
-                    call_type : CallTypes::OpenKan,
-                    });
-            }
-            if possible_calls.closed_kan && game.curr_player_idx == self_index
-            {
-                all_possible_calls.push(
-                    CalledSet {
-                        set : Set {
-                            set_type : SetType::Kan, // closed kan happens at discard
-                            tiles : vec![discarded_tile ; 4],
-                        },
-                    call_type : CallTypes::ClosedKan,
-                    });
-            }
-            // added kan is only possible during drawing. Not for calling on other player's discarded tiles
-            if possible_calls.chii && (game.curr_player_idx + 1) % NUM_PLAYERS == self_index
-            {
-                let mut chiiable_sets = get_callable_chii_combinations_with_tile(&game.players[self_index].hand, discarded_tile);
-                all_possible_calls.append(&mut chiiable_sets);
-            }
-            if possible_calls.ron
-            {
-                all_possible_calls.push(
-                    CalledSet {
-                        set : possible_calls.ron_set.clone(),
-                        call_type: CallTypes::Ron(possible_calls.ron_set.set_type)
-                    });
-            }
-
             tui_output::output_game(game, self_index);
             let call_made = tui_output::get_player_call_choice(game, self_index, discarded_tile, &mut all_possible_calls);
 
@@ -1279,6 +1356,26 @@ impl Player
         }
     }
 
+    /// Asks a player who can ron on a tile added to a kan whether they want to rob the kan (chankan)
+    pub fn choose_whether_to_rob_kan(self_index : usize, kanned_tile : Tile, game : &mut Game) -> Option<CalledSet>
+    {
+        let ron_set = game.players[self_index].callable_tiles[&kanned_tile].ron_set.clone();
+        let ron_call = vec![
+            CalledSet {
+                call_type : CallTypes::Ron(ron_set.set_type),
+                set : ron_set,
+            }
+        ];
+
+        if ! game.players[self_index].is_human
+        {
+            return game.ai_choose_call(self_index, kanned_tile, &ron_call);
+        }
+
+        tui_output::output_game(game, self_index);
+        tui_output::get_player_call_choice(game, self_index, kanned_tile, &ron_call)
+    }
+
     /// Looks through a hand and returns a tuple containing (pairs found, other tiles in the hand aside from the pair).
     /// If a tile pair has already been ron'd or tsumo'd into the revealed sets, then it returns early with just that pair
     /// since that will be the winning hand
@@ -1410,7 +1507,7 @@ impl Player
         return (0, 0, 0);
     }
 
-    fn hand_is_closed(&self) -> bool
+    pub fn hand_is_closed(&self) -> bool
     {
         if self.called_sets.len() == 0
         {
@@ -1420,8 +1517,8 @@ impl Player
         {
             for set in &self.called_sets
             {
-                // hand is still closed if it's only closed kans, or a set was created from ron
-                if set.call_type != CallTypes::ClosedKan
+                // hand is still closed if it's only closed kans, or a set was created from ron or tsumo
+                if set.call_type != CallTypes::ClosedKan && set.call_type != CallTypes::Tsumo
                 {
                     if let CallTypes::Ron(_) = set.call_type
                     {
@@ -1437,6 +1534,92 @@ impl Player
         }
     }
 
+    /// the concealed hand along with the set completed by a ron or tsumo, which was moved to the called sets when the player won
+    pub fn hand_with_winning_tile(&self) -> Vec<Tile>
+    {
+        let mut tiles = self.hand.clone();
+
+        for called_set in &self.called_sets
+        {
+            if let CallTypes::Ron(_) | CallTypes::Tsumo = called_set.call_type
+            {   tiles.extend(called_set.set.tiles.iter());  }
+        }
+
+        tiles.sort();
+        tiles
+    }
+
+    /// Returns every way the player's winning hand can be read as sets, with the set the winning tile (last_picked_tile)
+    /// completed and the wait it was won on. Returns an empty vector if the hand isn't complete.
+    pub fn winning_hand_interpretations(&self) -> Vec<HandInterpretation>
+    {
+        let mut interpretations : Vec<HandInterpretation> = vec![];
+
+        let won_by_ron = matches!(self.ron_or_tsumo, WinningMethod::Ron(_));
+
+        // tiles from a ron or tsumo were in the hand until the player won, so they're read along with the hand
+        let mut concealed_tiles = self.hand.clone();
+        let mut closed_kans : Vec<Set> = vec![];
+        let mut called_sets : Vec<Set> = vec![];
+
+        for called_set in &self.called_sets
+        {
+            match called_set.call_type {
+                CallTypes::Ron(_) | CallTypes::Tsumo => concealed_tiles.extend(called_set.set.tiles.iter()),
+                CallTypes::ClosedKan => closed_kans.push(called_set.set.clone()),
+                _ => called_sets.push(called_set.set.clone()),
+            }
+        }
+
+        concealed_tiles.sort();
+
+        let mut possible_hands = find_complete_hand_sets(&concealed_tiles);
+
+        // seven pairs. Four of the same tile doesn't count as two pairs
+        if concealed_tiles.len() == 14 && self.hand_is_closed() && closed_kans.is_empty()
+        {
+            let pairs : Vec<Set> = concealed_tiles.chunks(2).map(Set::from_tiles).collect();
+
+            if pairs.iter().all(|pair| pair.tiles[0] == pair.tiles[1])
+            && pairs.windows(2).all(|two_pairs| two_pairs[0].tiles[0] != two_pairs[1].tiles[0])
+            {
+                possible_hands.push(pairs);
+            }
+        }
+
+        for sets in possible_hands
+        {
+            for (winning_idx, winning_set) in sets.iter().enumerate()
+            {
+                if ! winning_set.tiles.contains(&self.last_picked_tile)
+                {   continue;   }
+
+                let mut interpretation = HandInterpretation {
+                    concealed_sets : closed_kans.clone(),
+                    open_sets : called_sets.clone(),
+                    winning_set : winning_set.clone(),
+                    wait : WaitType::from_winning_set(winning_set, self.last_picked_tile),
+                };
+
+                for (i, set) in sets.iter().enumerate()
+                {
+                    // a set finished with another player's discard isn't concealed
+                    if i == winning_idx && won_by_ron
+                    {   interpretation.open_sets.push(set.clone());         }
+                    else
+                    {   interpretation.concealed_sets.push(set.clone());    }
+                }
+
+                if ! interpretations.contains(&interpretation)
+                {
+                    interpretations.push(interpretation);
+                }
+            }
+        }
+
+        interpretations
+    }
+
     /// returns a pair from the hand or revealed sets. Used to find the pair from winning hands for fu calculation
     /// in the case of the yakuman of all pairs, simply returns the first pair it finds
     fn get_one_pair(&self) -> Option<Set>
@@ -1507,7 +1690,7 @@ impl Player
 }
 
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum WinningMethod {
     NotWonYet,
     /// contains the index of the person we ron'd on
@@ -1517,15 +1700,30 @@ pub enum WinningMethod {
 
 pub enum DiscardChoices {
     DiscardTile(usize),
+    /// declare riichi while discarding the tile at this index
+    Riichi(usize),
     Win,
     OpenClosedKan(Tile),
     AddedKan(Tile),
+    /// end the hand in an abortive draw with nine different terminals and honors on the first draw
+    KyuushuKyuuhai,
+    /// set a north aside in sanma and draw a replacement tile
+    NukiDora,
 }
 
+/// the built in computer players. Any other AIStrategy can be given to a player with Game::set_player_strategy
 #[derive(Clone, Eq, PartialEq)]
 pub enum AIAlgorithm {
     DumbAsBricks,
     SimpleDiscardAlwaysCall,
+    /// discards for the fewest shanten, then the most ukeire
+    EfficientDiscardAlwaysCall,
+    /// discards like EfficientDiscardAlwaysCall, but folds or pushes against riichi and opponents who look tenpai
+    DefensiveDiscardAlwaysCall,
+    /// discards like DefensiveDiscardAlwaysCall, and only calls when it speeds up a hand that keeps a yaku
+    DefensiveDiscardSmartCall,
+    /// discards for the best expected score or placement by playing out its next draws from sampled walls, and calls like DefensiveDiscardSmartCall
+    MonteCarloDiscardSmartCall(MonteCarloSettings),
 }
 
 
@@ -1649,6 +1847,7 @@ fn test_check_complete_hand_and_update_waits()
 
 
     const NUM_RAND_TESTS : usize = 100;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
 
     // TODO: Add called sets in revealed sets, tenpai hand testing, and incorrect hand testing
     for i in 0..NUM_RAND_TESTS
@@ -1657,17 +1856,17 @@ fn test_check_complete_hand_and_update_waits()
 
         for i in 0..5
         {
-            let new_set = match rand::thread_rng().gen_range(0..3) {
-                0 => utils::get_random_sequence(),
-                1 => utils::get_random_pair_triplet_or_kan(3),
-                2 => utils::get_random_pair_triplet_or_kan(4),
+            let new_set = match rng.gen_range(0..3) {
+                0 => utils::get_random_sequence(&mut rng),
+                1 => utils::get_random_pair_triplet_or_kan(&mut rng, 3),
+                2 => utils::get_random_pair_triplet_or_kan(&mut rng, 4),
                 _ => panic!()
             };
 
             sets.push(new_set);
         }
 
-        sets.push(utils::get_random_pair_triplet_or_kan(2));
+        sets.push(utils::get_random_pair_triplet_or_kan(&mut rng, 2));
 
         let mut hand : Vec<Tile> = vec![];
 
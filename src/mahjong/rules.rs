@@ -0,0 +1,275 @@
+use crate::mahjong::*;
+
+/// which abortive draws are played
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AbortiveDrawRules {
+    pub kyuushu_kyuuhai : bool,
+    pub suufon_renda : bool,
+    pub suucha_riichi : bool,
+    pub suukaikan : bool,
+    /// when unset, the triple ron rule decides who wins
+    pub sanchahou : bool,
+}
+
+impl Default for AbortiveDrawRules {
+    fn default() -> Self {
+        AbortiveDrawRules {
+            kyuushu_kyuuhai : true,
+            suufon_renda : true,
+            suucha_riichi : true,
+            suukaikan : true,
+            sanchahou : true,
+        }
+    }
+}
+
+impl AbortiveDrawRules {
+    /// every hand is played until someone wins or the wall runs out
+    pub fn none() -> Self
+    {
+        AbortiveDrawRules {
+            kyuushu_kyuuhai : false,
+            suufon_renda : false,
+            suucha_riichi : false,
+            suukaikan : false,
+            sanchahou : false,
+        }
+    }
+}
+
+/// what happens when two players call ron on the same tile
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DoubleRonRule {
+    /// both players win, and are each paid by the discarder
+    BothWin,
+    /// head bump. Only the first player in turn order after the discarder wins
+    Atamahane,
+}
+
+/// what happens when three players call ron on the same tile, if sanchahou isn't played
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TripleRonRule {
+    /// all three players win, and are each paid by the discarder
+    AllWin,
+    /// head bump. Only the first player in turn order after the discarder wins
+    Atamahane,
+}
+
+/// how nagashi mangan is paid at an exhaustive draw
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NagashiManganRule {
+    Disabled,
+    /// nagashi mangan is paid, and no one pays for being noten
+    InsteadOfNotenPayments,
+    /// nagashi mangan is paid, then noten payments are made as usual
+    AlongsideNotenPayments,
+}
+
+/// how many wind rounds make up a game
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameLength {
+    /// east round only
+    Tonpuusen,
+    /// east and south rounds
+    Hanchan,
+}
+
+impl GameLength {
+    pub fn num_rounds(&self) -> usize
+    {
+        match self {
+            GameLength::Tonpuusen => 1,
+            GameLength::Hanchan => 2,
+        }
+    }
+}
+
+/// how the missing north player's share of a tsumo is handled in sanma
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SanmaTsumoPayment {
+    /// tsumo loss. The winner just isn't paid the north player's share
+    TsumoLoss,
+    /// the north player's share is split between the two players who pay
+    NorthBisection,
+}
+
+/// placement bonuses added to the final scores, in thousands of points.
+/// For example 10-20 uma gives +20, +10, -10 and -20. Three player games give +big, 0 and -big
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Uma {
+    pub small : i32,
+    pub big : i32,
+}
+
+impl Uma {
+    /// the uma for each placement, from first to last
+    pub fn by_placement(&self, num_players : usize) -> Vec<i32>
+    {
+        if num_players == NUM_SANMA_PLAYERS
+        {   vec![self.big, 0, -self.big]   }
+        else
+        {   vec![self.big, self.small, -self.small, -self.big]   }
+    }
+}
+
+/// Every rule which differs between rule sets. Game, scoring and call handling read their rules from here
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RuleSet {
+    pub game_length : GameLength,
+    pub starting_points : i32,
+
+    /// red fives in each of the man, pin and sou suits
+    pub red_fives_per_suit : usize,
+    /// open tanyao. Without it, tanyao needs a closed hand
+    pub kuitan : bool,
+    /// hands like daisuushi or a 13 sided kokushi wait are worth two yakuman. Otherwise each yakuman is worth one
+    pub double_yakuman : bool,
+    /// 4 han 30 fu and 3 han 60 fu are rounded up to mangan
+    pub kiriage_mangan : bool,
+
+    /// the game ends as soon as a player's points go below zero
+    pub tobi : bool,
+    /// the dealer can end the game by winning the last hand while in first place
+    pub agari_yame : bool,
+    /// when no one has reached the target points at the end of the game, one more wind round is played.
+    /// It ends as soon as someone reaches the target
+    pub west_extension : bool,
+    pub target_points : i32,
+
+    /// final scores are counted from this many points. The difference from the starting points of each player is the oka, which goes to first place
+    pub return_points : i32,
+    pub uma : Uma,
+
+    /// points split between noten players and tenpai players at an exhaustive draw
+    pub noten_payment_points : i32,
+    pub nagashi_mangan : NagashiManganRule,
+
+    pub double_ron : DoubleRonRule,
+    pub triple_ron : TripleRonRule,
+    pub abortive_draws : AbortiveDrawRules,
+
+    /// three player mode when set
+    pub sanma : Option<SanmaTsumoPayment>,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet::tenhou()
+    }
+}
+
+impl RuleSet {
+    /// the rules of Tenhou's ranked lobbies
+    pub fn tenhou() -> Self
+    {
+        RuleSet {
+            game_length : GameLength::Hanchan,
+            starting_points : 25000,
+            red_fives_per_suit : 1,
+            kuitan : true,
+            double_yakuman : false,
+            kiriage_mangan : false,
+            tobi : true,
+            agari_yame : true,
+            west_extension : true,
+            target_points : 30000,
+            return_points : 30000,
+            uma : Uma { small : 10, big : 20 },
+            noten_payment_points : 3000,
+            nagashi_mangan : NagashiManganRule::InsteadOfNotenPayments,
+            double_ron : DoubleRonRule::BothWin,
+            triple_ron : TripleRonRule::AllWin,
+            abortive_draws : AbortiveDrawRules::default(),
+            sanma : None,
+        }
+    }
+
+    /// the rules of Tenhou's three player lobbies
+    pub fn tenhou_sanma() -> Self
+    {
+        RuleSet {
+            starting_points : 35000,
+            target_points : 40000,
+            return_points : 40000,
+            uma : Uma { small : 0, big : 15 },
+            noten_payment_points : 2000,
+            sanma : Some(SanmaTsumoPayment::TsumoLoss),
+            ..RuleSet::tenhou()
+        }
+    }
+
+    /// European Mahjong Association riichi rules
+    pub fn ema() -> Self
+    {
+        RuleSet {
+            game_length : GameLength::Hanchan,
+            starting_points : 30000,
+            red_fives_per_suit : 0,
+            kuitan : true,
+            double_yakuman : false,
+            kiriage_mangan : false,
+            tobi : false,
+            agari_yame : false,
+            west_extension : false,
+            target_points : 30000,
+            return_points : 30000,
+            uma : Uma { small : 5, big : 15 },
+            noten_payment_points : 3000,
+            nagashi_mangan : NagashiManganRule::Disabled,
+            double_ron : DoubleRonRule::BothWin,
+            triple_ron : TripleRonRule::AllWin,
+            abortive_draws : AbortiveDrawRules::none(),
+            sanma : None,
+        }
+    }
+
+    /// World Riichi Championship rules
+    pub fn wrc() -> Self
+    {
+        RuleSet {
+            game_length : GameLength::Hanchan,
+            starting_points : 30000,
+            red_fives_per_suit : 0,
+            kuitan : true,
+            double_yakuman : false,
+            kiriage_mangan : false,
+            tobi : false,
+            agari_yame : false,
+            west_extension : false,
+            target_points : 30000,
+            return_points : 30000,
+            uma : Uma { small : 5, big : 15 },
+            noten_payment_points : 3000,
+            nagashi_mangan : NagashiManganRule::Disabled,
+            double_ron : DoubleRonRule::Atamahane,
+            triple_ron : TripleRonRule::Atamahane,
+            abortive_draws : AbortiveDrawRules::none(),
+            sanma : None,
+        }
+    }
+
+    /// rules of the M-League professional league
+    pub fn m_league() -> Self
+    {
+        RuleSet {
+            game_length : GameLength::Hanchan,
+            starting_points : 25000,
+            red_fives_per_suit : 1,
+            kuitan : true,
+            double_yakuman : false,
+            kiriage_mangan : true,
+            tobi : false,
+            agari_yame : false,
+            west_extension : false,
+            target_points : 30000,
+            return_points : 30000,
+            uma : Uma { small : 10, big : 30 },
+            noten_payment_points : 3000,
+            nagashi_mangan : NagashiManganRule::AlongsideNotenPayments,
+            double_ron : DoubleRonRule::Atamahane,
+            triple_ron : TripleRonRule::Atamahane,
+            abortive_draws : AbortiveDrawRules { sanchahou : false, ..AbortiveDrawRules::default() },
+            sanma : None,
+        }
+    }
+}
@@ -1,16 +1,377 @@
 use crate::mahjong::player::*;
 use crate::mahjong::*;
 
-    pub fn yaku_chiitoitsu(player : &Player, game : &Game) -> usize
+    /// returns closed_han if the hand is closed, and open_han if not. Many yaku are worth one less han when opened
+    fn closed_or_open_han(player : &Player, closed_han : usize, open_han : usize) -> usize
     {
+        if player.hand_is_closed()
+        {   closed_han  }
+        else
+        {   open_han    }
+    }
+
+    pub fn is_yakuhai_tile(player : &Player, game : &Game, tile : Tile) -> bool
+    {
+        tile.is_dragon()
+        || (tile.suit == Suit::Honor && (tile.value == player.seat_wind || tile.value == game.round_wind))
+    }
+
+    /// number of pairs of identical sequences. Four identical sequences counts as two pairs
+    fn num_identical_sequence_pairs(hand : &HandInterpretation) -> usize
+    {
+        let mut sequences = hand.sequences();
+        sequences.sort();
+
+        let mut num_pairs = 0;
+        let mut i = 0;
+        while i + 1 < sequences.len()
+        {
+            if sequences[i] == sequences[i + 1]
+            {
+                num_pairs += 1;
+                i += 2;
+            }
+            else
+            {
+                i += 1;
+            }
+        }
+
+        num_pairs
+    }
+
+    pub fn yaku_riichi(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        (player.riichi && ! player.double_riichi) as usize
+    }
+
+    pub fn yaku_double_riichi(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if player.double_riichi { 2 } else { 0 }
+    }
+
+    pub fn yaku_ippatsu(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        ((player.riichi || player.double_riichi) && player.iipatsu) as usize
+    }
+
+    // closed hand won by self draw
+    pub fn yaku_menzenchin_tsumohou(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        (player.hand_is_closed() && player.ron_or_tsumo == WinningMethod::Tsumo) as usize
+    }
+
+    // seven pairs
+    pub fn yaku_chiitoitsu(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if hand.is_seven_pairs() { 2 } else { 0 }
+    }
+
+    // closed hand of only sequences, a pair that isn't yakuhai, and won on a two sided wait
+    pub fn yaku_pinfu(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if ! player.hand_is_closed() || hand.is_seven_pairs()
+        {   return 0;   }
+
+        if hand.sequences().len() != 4 || hand.wait != WaitType::Ryanmen
+        {   return 0;   }
+
+        match hand.pair() {
+            Some(pair) if ! is_yakuhai_tile(player, game, pair.tiles[0]) => 1,
+            _ => 0,
+        }
+    }
+
+    // two identical sequences
+    pub fn yaku_iipeikou(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        (player.hand_is_closed() && num_identical_sequence_pairs(hand) == 1) as usize
+    }
+
+    // two sets of two identical sequences
+    pub fn yaku_ryanpeikou(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if player.hand_is_closed() && num_identical_sequence_pairs(hand) == 2 { 3 } else { 0 }
+    }
+
+    // win by self draw on the last tile from the wall
+    pub fn yaku_haitei_raoyue(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        // the replacement tile from a kan isn't the last tile, even if the live wall is now empty
+        (player.ron_or_tsumo == WinningMethod::Tsumo && game.live_wall_is_empty() && ! game.drew_from_dead_wall) as usize
+    }
+
+    // win by ron on the last discard
+    pub fn yaku_houtei_raoyui(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        (matches!(player.ron_or_tsumo, WinningMethod::Ron(_)) && game.live_wall_is_empty() && ! game.kan_was_robbed) as usize
+    }
+
+    // win by ron on a tile another player added to their pon to make a kan
+    pub fn yaku_chankan(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        (matches!(player.ron_or_tsumo, WinningMethod::Ron(_)) && game.kan_was_robbed) as usize
+    }
+
+    // win by tsumo on the replacement tile drawn after a kan
+    pub fn yaku_rinshan_kaihou(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        (player.ron_or_tsumo == WinningMethod::Tsumo && game.drew_from_dead_wall) as usize
+    }
+
+    // all simples
+    pub fn yaku_tanyao(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        // without kuitan, tanyao has to be closed
+        if ! game.rules.kuitan && ! player.hand_is_closed()
+        {   return 0;   }
+
+        hand.all_tiles().all(|tile| ! tile.is_honor_or_terminal()) as usize
+    }
+
+    // one han for each triplet of dragons, the seat wind, or the round wind. A double wind is worth two
+    pub fn yaku_yakuhai(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let mut han = 0;
+
+        for set in hand.triplets()
+        {
+            let tile = set.tiles[0];
+
+            if tile.is_dragon()
+            {   han += 1;   }
+
+            if tile.suit == Suit::Honor && tile.value == player.seat_wind
+            {   han += 1;   }
+
+            if tile.suit == Suit::Honor && tile.value == game.round_wind
+            {   han += 1;   }
+        }
+
+        han
+    }
+
+    // every set and the pair contain a terminal or honor, with at least one sequence and one honor
+    pub fn yaku_chantaiyao(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if hand.is_seven_pairs() || hand.sequences().is_empty()
+        {   return 0;   }
+
+        if hand.all_sets().all(|set| set.has_honor_or_terminal())
+        && hand.all_tiles().any(|tile| tile.suit == Suit::Honor)
+        {
+            return closed_or_open_han(player, 2, 1);
+        }
+
+        0
+    }
+
+    // every set and the pair contain a terminal, with at least one sequence
+    pub fn yaku_junchantaiyao(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if hand.is_seven_pairs() || hand.sequences().is_empty()
+        {   return 0;   }
+
+        if hand.all_sets().all(|set| set.tiles.iter().any(|tile| tile.is_terminal()))
+        {
+            return closed_or_open_han(player, 3, 2);
+        }
+
+        0
+    }
+
+    // the same sequence in all three suits
+    pub fn yaku_sanshoku_doujun(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let sequences = hand.sequences();
+
+        for sequence in &sequences
+        {
+            let start = sequence.tiles[0].value;
+
+            if [Suit::Man, Suit::Pin, Suit::Sou].iter().all(
+                |suit| sequences.iter().any(|other| other.tiles[0].suit == *suit && other.tiles[0].value == start))
+            {
+                return closed_or_open_han(player, 2, 1);
+            }
+        }
+
+        0
+    }
+
+    // full straight. 123, 456, and 789 of one suit
+    pub fn yaku_ikkitsuukan(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let sequences = hand.sequences();
+
+        for suit in [Suit::Man, Suit::Pin, Suit::Sou]
+        {
+            if [SuitVal::One, SuitVal::Four, SuitVal::Seven].iter().all(
+                |start| sequences.iter().any(|sequence| sequence.tiles[0].suit == suit && sequence.tiles[0].value == *start))
+            {
+                return closed_or_open_han(player, 2, 1);
+            }
+        }
+
+        0
+    }
+
+    // all triplets
+    pub fn yaku_toitoi(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if hand.triplets().len() == 4 { 2 } else { 0 }
+    }
+
+    // three concealed triplets. A triplet finished by ron isn't concealed
+    pub fn yaku_sanankou(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let num_concealed_triplets = hand.concealed_sets.iter().filter(
+            |set| set.set_type == SetType::Triplet || set.set_type == SetType::Kan).count();
+
+        if num_concealed_triplets >= 3 { 2 } else { 0 }
+    }
+
+    // the same triplet in all three suits
+    pub fn yaku_sanshoku_doukou(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let triplets = hand.triplets();
+
+        for triplet in &triplets
+        {
+            let value = triplet.tiles[0].value;
+
+            if [Suit::Man, Suit::Pin, Suit::Sou].iter().all(
+                |suit| triplets.iter().any(|other| other.tiles[0].suit == *suit && other.tiles[0].value == value))
+            {
+                return 2;
+            }
+        }
+
         0
     }
 
-    pub fn yaku_pinfu(player : &Player, game : &Game) -> usize
+    // three kans
+    pub fn yaku_sankantsu(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
     {
+        if hand.all_sets().filter(|set| set.set_type == SetType::Kan).count() == 3 { 2 } else { 0 }
+    }
+
+    // only terminals and honors, with at least one of each
+    pub fn yaku_honroutou(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        if hand.all_tiles().all(|tile| tile.is_honor_or_terminal())
+        && hand.all_tiles().any(|tile| tile.suit == Suit::Honor)
+        && hand.all_tiles().any(|tile| tile.is_terminal())
+        {
+            return 2;
+        }
+
+        0
+    }
+
+    // two dragon triplets and a dragon pair
+    pub fn yaku_shousangen(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let num_dragon_triplets = hand.triplets().iter().filter(|set| set.tiles[0].is_dragon()).count();
+
+        match hand.pair() {
+            Some(pair) if num_dragon_triplets == 2 && pair.tiles[0].is_dragon() => 2,
+            _ => 0,
+        }
+    }
+
+    // one suit along with honors
+    pub fn yaku_honitsu(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let mut numbered_tiles = hand.all_tiles().filter(|tile| tile.suit != Suit::Honor);
+        let first_suit = match numbered_tiles.next() {
+            Some(tile) => tile.suit,
+            None => return 0,
+        };
+
+        if numbered_tiles.all(|tile| tile.suit == first_suit)
+        && hand.all_tiles().any(|tile| tile.suit == Suit::Honor)
+        {
+            return closed_or_open_han(player, 3, 2);
+        }
+
+        0
+    }
+
+    // one suit without any honors
+    pub fn yaku_chinitsu(player : &Player, game : &Game, hand : &HandInterpretation) -> usize
+    {
+        let first_suit = hand.winning_set.tiles[0].suit;
+
+        if first_suit != Suit::Honor && hand.all_tiles().all(|tile| tile.suit == first_suit)
+        {
+            return closed_or_open_han(player, 6, 5);
+        }
+
         0
     }
 
+/// checks a single yaku for one interpretation of a winning hand, and returns the han it's worth
+pub type YakuFunc = dyn Fn(&Player, &Game, &HandInterpretation) -> usize;
+
+pub const YAKU_FUNCS : [ (YakuType, &YakuFunc) ; 26] = [
+    (YakuType::Riichi, &yaku_riichi),
+    (YakuType::Ippatsu, &yaku_ippatsu),
+    (YakuType::MenzenchinTsumohou, &yaku_menzenchin_tsumohou),
+    (YakuType::Pinfu, &yaku_pinfu),
+    (YakuType::Iipeikou, &yaku_iipeikou),
+    (YakuType::HaiteiRaoyue, &yaku_haitei_raoyue),
+    (YakuType::HouteiRaoyui, &yaku_houtei_raoyui),
+    (YakuType::RinshanKaihou, &yaku_rinshan_kaihou),
+    (YakuType::Chankan, &yaku_chankan),
+    (YakuType::Tanyao, &yaku_tanyao),
+    (YakuType::Yakuhai, &yaku_yakuhai),
+    (YakuType::DoubleRiichi, &yaku_double_riichi),
+    (YakuType::Chantaiyao, &yaku_chantaiyao),
+    (YakuType::SanshokuDoujun, &yaku_sanshoku_doujun),
+    (YakuType::Ikkitsuukan, &yaku_ikkitsuukan),
+    (YakuType::Toitoi, &yaku_toitoi),
+    (YakuType::Sanankou, &yaku_sanankou),
+    (YakuType::SanshokuDoukou, &yaku_sanshoku_doukou),
+    (YakuType::Sankantsu, &yaku_sankantsu),
+    (YakuType::Chiitoitsu, &yaku_chiitoitsu),
+    (YakuType::Honroutou, &yaku_honroutou),
+    (YakuType::Shousangen, &yaku_shousangen),
+    (YakuType::Honitsu, &yaku_honitsu),
+    (YakuType::Junchantaiyao, &yaku_junchantaiyao),
+    (YakuType::Ryanpeikou, &yaku_ryanpeikou),
+    (YakuType::Chinitsu, &yaku_chinitsu),
+];
+
+/// converts han and fu into basic points, capping the hand at mangan and above
+pub fn basic_points_from_han_and_fu(han : usize, fu : usize) -> usize
+{
+    // don't score fu if 5 han or above
+    if han >= 5
+    {
+        if han == 5
+        {   2000    }
+        else if han <= 7
+        {   3000    }
+        else if han <= 10
+        {   4000    }
+        else if han <= 12
+        {   6000    }
+        else // hand counted as yakuman if there's enough han
+        {   8000   }
+    }
+    else
+    {
+        let basic_points = fu * pow(2, 2 + han);
+
+        // if han and fu reach over 2000 points, it's considered a 2000 point mangan
+        if basic_points > 2000
+        {   2000            }
+        else
+        {   basic_points    }
+    }
+}
+
     // three great dragons
     pub fn yakuman_daisangen(player : &Player, game : &Game) -> usize
     {
@@ -23,32 +384,15 @@ use crate::mahjong::*;
 
     // thirteen orphans
     pub fn yakuman_kokushi_musou(player : &Player, game : &Game) -> usize
-    { // TODO: Double yakuman if the wait was on the pair
-        if  player.tiles_contain(Suit::Man, SuitVal::One)
-        &&  player.tiles_contain(Suit::Man, SuitVal::Nine)
-        &&  player.tiles_contain(Suit::Pin, SuitVal::One)
-        &&  player.tiles_contain(Suit::Pin, SuitVal::Nine)
-        &&  player.tiles_contain(Suit::Sou, SuitVal::One)
-        &&  player.tiles_contain(Suit::Sou, SuitVal::Nine)
-        &&  player.tiles_contain(Suit::Honor, SuitVal::North)
-        &&  player.tiles_contain(Suit::Honor, SuitVal::East)
-        &&  player.tiles_contain(Suit::Honor, SuitVal::South)
-        &&  player.tiles_contain(Suit::Honor, SuitVal::West)
-        &&  player.tiles_contain(Suit::Honor, SuitVal::Red)
-        &&  player.tiles_contain(Suit::Honor, SuitVal::White)
-        &&  player.tiles_contain(Suit::Honor, SuitVal::Green)
-        &&  player.hand_num_pairs() == 1
-        {
-            // double yakuman if there was a 13 sided wait for the last tile
-            if player.tiles_num_of(player.last_picked_tile.suit, player.last_picked_tile.value) == 2
-            {   2   }
-            else
-            {   1   }
-        }
+    {
+        if ! is_kokushi(&player.hand_with_winning_tile())
+        {   return 0;   }
+
+        // double yakuman if there was a 13 sided wait for the last tile
+        if player.tiles_num_of(player.last_picked_tile.suit, player.last_picked_tile.value) == 2
+        {   2   }
         else
-        {
-            0
-        }
+        {   1   }
     }
 
     // four concealed triplets and a pair
@@ -146,28 +490,40 @@ use crate::mahjong::*;
 
     // TODO: The opened door? Forget the english translation. Full straight with extra terminals
     pub fn yakuman_chuuren_poutou(player : &Player, game : &Game) -> usize
-    { // TODO: Double yakuman if the last tile chosen was the extra tile
+    {
+        let mut tiles = player.hand_with_winning_tile();
 
-        // check for all tiles being the same suit
-        for i in 1..player.hand.len()
+        // check for a closed hand with all tiles being the same suit
+        if tiles.len() != 14 || tiles.iter().any(|tile| tile.suit != tiles[0].suit)
         {
-            if player.hand[i].suit != player.hand[0].suit
-            {
-                return 0;
-            }
+            return 0;
         }
 
-        return
-            ( player.hand.iter().filter(|&t| t.value == SuitVal::One).count() >= 3
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Two).count() >= 1
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Three).count() >= 1
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Four).count() >= 1
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Five).count() >= 1
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Six).count() >= 1
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Seven).count() >= 1
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Eight).count() >= 1
-            && player.hand.iter().filter(|&t| t.value == SuitVal::Nine).count() >= 3
-            ) as usize;
+        let is_chuuren =
+            tiles.iter().filter(|&t| t.value == SuitVal::One).count() >= 3
+            && tiles.iter().filter(|&t| t.value == SuitVal::Two).count() >= 1
+            && tiles.iter().filter(|&t| t.value == SuitVal::Three).count() >= 1
+            && tiles.iter().filter(|&t| t.value == SuitVal::Four).count() >= 1
+            && tiles.iter().filter(|&t| t.value == SuitVal::Five).count() >= 1
+            && tiles.iter().filter(|&t| t.value == SuitVal::Six).count() >= 1
+            && tiles.iter().filter(|&t| t.value == SuitVal::Seven).count() >= 1
+            && tiles.iter().filter(|&t| t.value == SuitVal::Eight).count() >= 1
+            && tiles.iter().filter(|&t| t.value == SuitVal::Nine).count() >= 3;
+
+        if ! is_chuuren
+        {   return 0;   }
+
+        // double yakuman if the hand was waiting on all nine tiles before the last tile
+        let winning_tile_idx = tiles.iter().position(|tile| *tile == player.last_picked_tile);
+        if let Some(winning_tile_idx) = winning_tile_idx
+        {
+            tiles.remove(winning_tile_idx);
+
+            if is_nine_sided_chuuren(&tiles)
+            {   return 2;   }
+        }
+
+        1
     }
 
 
@@ -212,7 +568,7 @@ use crate::mahjong::*;
     {
         // TODO: If any tile call made by any player, does it interrupt this? Or only if this player has called? For now, assuming any player
         // also assuming that calling a closed kan eliminates possibility of this hand
-        if game.next_tile < NUM_PLAYERS
+        if game.next_tile < game.num_players()
         && game.next_tile != 0
         && game.num_called_tiles == 0
         && player.seat_wind == SuitVal::East
@@ -225,21 +581,39 @@ use crate::mahjong::*;
     }
 
 
-pub const YAKUMAN_FUNCS : [ &dyn Fn(&Player, &Game) -> usize ; 11] = [
-    &yakuman_daisangen,
-    &yakuman_kokushi_musou,
-    &yakuman_suuankou,
-    &yakuman_suushiihou,
-    &yakuman_tsuuiisou,
-    &yakuman_ryuuiisou,
-    &yakuman_chinroutou,
-    &yakuman_chuuren_poutou,
-    &yakuman_suukantsu,
-    &yakuman_tenhou,
-    &yakuman_chiihou,
+    // three little winds
+    pub fn yakuman_shousuushi(player : &Player, game : &Game) -> usize
+    {
+        (yakuman_suushiihou(player, game) == 1) as usize
+    }
+
+    // four great winds
+    pub fn yakuman_daisuushi(player : &Player, game : &Game) -> usize
+    {
+        if yakuman_suushiihou(player, game) == 2 { 2 } else { 0 }
+    }
+
+
+/// checks a single yakuman for a winning hand, and returns how many yakuman it's worth
+pub type YakumanFunc = dyn Fn(&Player, &Game) -> usize;
+
+pub const YAKUMAN_FUNCS : [ (YakuType, &YakumanFunc) ; 12] = [
+    (YakuType::Daisangen, &yakuman_daisangen),
+    (YakuType::KokushiMusou, &yakuman_kokushi_musou),
+    (YakuType::Suuankou, &yakuman_suuankou),
+    (YakuType::Shousuushi, &yakuman_shousuushi),
+    (YakuType::Daisuushi, &yakuman_daisuushi),
+    (YakuType::Tsuuiisou, &yakuman_tsuuiisou),
+    (YakuType::Ryuuiisou, &yakuman_ryuuiisou),
+    (YakuType::Chinroutou, &yakuman_chinroutou),
+    (YakuType::ChuurenPoutou, &yakuman_chuuren_poutou),
+    (YakuType::Suukantsu, &yakuman_suukantsu),
+    (YakuType::Tenhou, &yakuman_tenhou),
+    (YakuType::Chiihou, &yakuman_chiihou),
 ];
 
-enum YakuType {
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum YakuType {
 // closed only and 1 han
     Riichi,
     Ippatsu,
@@ -291,110 +665,419 @@ enum YakuType {
     Daisharin,
 }
 
-struct HandScore {
-    basic_points : usize,
-    fu : usize,
+/// The highest value a hand reached. Hands at mangan and above are paid a fixed amount, regardless of fu
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HandLimit {
+    NoLimit,
+    Mangan,
+    Haneman,
+    Baiman,
+    Sanbaiman,
+    /// 13 or more han without a yakuman
+    KazoeYakuman,
+    /// contains the number of yakuman the hand is worth
+    Yakuman(usize),
+}
 
-    calculated_point_dif : i32,
+impl HandLimit {
+    fn from_han_and_basic_points(han : usize, basic_points : usize) -> HandLimit
+    {
+        match han {
+            0..=4 if basic_points < 2000 => HandLimit::NoLimit,
+            0..=5 => HandLimit::Mangan,
+            6..=7 => HandLimit::Haneman,
+            8..=10 => HandLimit::Baiman,
+            11..=12 => HandLimit::Sanbaiman,
+            _ => HandLimit::KazoeYakuman,
+        }
+    }
+}
 
-    hand_yaku : Vec<YakuType>,
+/// Where a hand's fu came from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FuComponent {
+    /// every winning hand starts with 20 fu
+    Base,
+    /// seven pairs is always 25 fu, and nothing else is added
+    Chiitoitsu,
+    /// a triplet or kan made entirely from the player's own tiles
+    ConcealedSet(Set),
+    /// a triplet or kan that was called, or finished by ron
+    OpenSet(Set),
+    /// a pair of dragons, the seat wind, or the round wind
+    YakuhaiPair(Set),
+    Wait(WaitType),
+    /// winning by ron with a closed hand
+    MenzenRon,
+    Tsumo,
+    /// an open hand that would otherwise have 20 fu is raised to 30
+    OpenPinfu,
 }
 
+/// The full breakdown of how a winning hand was scored
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HandScore {
+    /// each yaku in the hand with the han it's worth. For yakuman hands this is each yakuman with how many yakuman it's worth
+    pub hand_yaku : Vec<(YakuType, usize)>,
+    pub dora : usize,
+    pub aka_dora : usize,
+    pub ura_dora : usize,
+    /// total han, including dora
+    pub han : usize,
 
-pub fn score_points(game : &mut Game, winning_player_idx : Option<usize>) -> ()
+    /// fu after rounding. 0 for yakuman
+    pub fu : usize,
+    pub fu_components : Vec<(FuComponent, usize)>,
+
+    pub limit : HandLimit,
+    pub basic_points : usize,
+
+    /// repeat counters paid on top of the hand's value
+    pub honba : usize,
+    /// riichi deposits on the table, which all go to the winner
+    pub riichi_sticks : usize,
+
+    /// change to each player's points, indexed the same as the game's players. The winner's is positive and everyone who paid is negative.
+    /// Includes honba and riichi sticks
+    pub point_changes : Vec<i32>,
+
+    /// the interpretation of the hand that scored the highest. None for yakuman, which don't need one
+    pub hand_interpretation : Option<HandInterpretation>,
+}
+
+/// Scores a player's winning hand without working out who pays for it. point_changes are left at 0
+pub fn evaluate_hand(player : &Player, game : &Game) -> HandScore
+{
+    evaluate_hand_counting_ura_dora(player, game, true)
+}
+
+/// scores a winning hand like evaluate_hand, as it looks before the ura dora indicators are turned over
+pub fn evaluate_hand_without_ura_dora(player : &Player, game : &Game) -> HandScore
+{
+    evaluate_hand_counting_ura_dora(player, game, false)
+}
+
+fn evaluate_hand_counting_ura_dora(player : &Player, game : &Game, count_ura_dora : bool) -> HandScore
+{
+    let mut hand_score = HandScore {
+        hand_yaku : vec![],
+        dora : 0,
+        aka_dora : 0,
+        ura_dora : 0,
+        han : 0,
+        fu : 0,
+        fu_components : vec![],
+        limit : HandLimit::NoLimit,
+        basic_points : 0,
+        honba : 0,
+        riichi_sticks : 0,
+        point_changes : vec![0; game.num_players()],
+        hand_interpretation : None,
+    };
+
+    // double yakuman, come on!
+    for (yaku_type, yakuman) in YAKUMAN_FUNCS
+    {
+        let num_yakuman = if game.rules.double_yakuman
+        {   yakuman(player, game)           }
+        else
+        {   yakuman(player, game).min(1)    };
+
+        if num_yakuman > 0
+        {
+            hand_score.hand_yaku.push((yaku_type, num_yakuman));
+        }
+    }
+
+    if ! hand_score.hand_yaku.is_empty()
     {
-        if let Some(winning_player_idx) = winning_player_idx
+        let num_yakuman : usize = hand_score.hand_yaku.iter().map(|(_, num)| num).sum();
+
+        // 8000 basic points per yakuman
+        hand_score.limit = HandLimit::Yakuman(num_yakuman);
+        hand_score.basic_points = 8000 * num_yakuman;
+        return hand_score;
+    }
+
+    let dora = player.hand_dora_in_han(game);
+    let aka_dora = player.hand_aka_dora_in_han();
+    let ura_dora = if count_ura_dora { player.hand_ura_dora_in_han(game) } else { 0 };
+
+    // a hand is scored using whichever interpretation of it is worth the most
+    for interpretation in player.winning_hand_interpretations()
+    {
+        let mut hand_yaku = vec![];
+        for (yaku_type, yaku) in YAKU_FUNCS
         {
-            if game.players[winning_player_idx].is_human
+            let han = yaku(player, game, &interpretation);
+            if han > 0
             {
-                tui_output::output_player_win_or_lose(&game.players[winning_player_idx], game.human_is_playing);
-            }
-            else {
-                tui_output::output_player_win_or_lose(&game.players[winning_player_idx], game.human_is_playing);
+                hand_yaku.push((yaku_type, han));
             }
         }
 
-        const EXHAUSTIVE_DRAW_POINTS : i32 = 3000;
+        // at least one yaku or yakuman is required to have a valid scoring hand
+        if hand_yaku.is_empty()
+        {   continue;   }
+
+        let han = hand_yaku.iter().map(|(_, han)| han).sum::<usize>() + dora + aka_dora + ura_dora;
+
+        let fu_components = player.interpretation_fu_components(game, &interpretation);
+        let fu = round_up_fu(fu_components.iter().map(|(_, fu)| fu).sum());
+        let mut basic_points = basic_points_from_han_and_fu(han, fu);
+
+        // 4 han 30 fu and 3 han 60 fu both come to 1920 basic points
+        if game.rules.kiriage_mangan && basic_points == KIRIAGE_MANGAN_BASIC_POINTS
+        {   basic_points = 2000;   }
+
+        if basic_points > hand_score.basic_points
+        {
+            hand_score = HandScore {
+                hand_yaku,
+                dora,
+                aka_dora,
+                ura_dora,
+                han,
+                fu,
+                fu_components,
+                limit : HandLimit::from_han_and_basic_points(han, basic_points),
+                basic_points,
+                hand_interpretation : Some(interpretation),
+                ..hand_score
+            };
+        }
+    }
+
+    hand_score
+}
+
+const KIRIAGE_MANGAN_BASIC_POINTS : usize = 1920;
+
+/// points paid for each honba. Split between every payer for tsumo
+pub const HONBA_POINTS : i32 = 300;
+pub const RIICHI_STICK_POINTS : i32 = 1000;
+
+/// Scores a winning hand, and works out how much each player pays for it, including the game's honba and riichi sticks.
+/// The game isn't changed. score_points applies the result
+pub fn score_hand(game : &Game, winning_player_idx : usize) -> HandScore
+{
+    score_hand_with_honba(game, winning_player_idx, game.honba)
+}
+
+/// scores a winning hand like score_hand, paid with this many honba instead of the game's
+fn score_hand_with_honba(game : &Game, winning_player_idx : usize, honba : usize) -> HandScore
+{
+    let winning_player = &game.players[winning_player_idx];
+    let mut hand_score = evaluate_hand(winning_player, game);
+    hand_score.honba = honba;
+    hand_score.riichi_sticks = game.riichi_sticks;
+
+    let basic_points = hand_score.basic_points as i32;
+    let honba_points = HONBA_POINTS * honba as i32;
+    let winner_is_dealer = winning_player.seat_wind == SuitVal::East;
+
+    hand_score.point_changes[winning_player_idx] += RIICHI_STICK_POINTS * game.riichi_sticks as i32;
+
+    match winning_player.ron_or_tsumo {
+        WinningMethod::Ron(victim_index) => { // "victim" is the player who got ron called on them
+            let payment = utils::round_up_to_100(basic_points * if winner_is_dealer { 6 } else { 4 }) + honba_points;
+
+            hand_score.point_changes[victim_index] -= payment;
+            hand_score.point_changes[winning_player_idx] += payment;
+        },
+        WinningMethod::Tsumo => add_tsumo_payments(game, &mut hand_score, winning_player_idx, honba_points),
+        WinningMethod::NotWonYet => panic!("Player won, but did not have ron or tsumo set"),
+    }
+
+    hand_score
+}
+
+/// every other player pays the winner. The dealer pays double, or everyone pays double when the dealer wins.
+/// In sanma the missing north player's share is either lost or split between the payers, depending on the rules
+fn add_tsumo_payments(game : &Game, hand_score : &mut HandScore, winning_player_idx : usize, honba_points : i32)
+{
+    let basic_points = hand_score.basic_points as i32;
+    let winner_is_dealer = game.players[winning_player_idx].seat_wind == SuitVal::East;
+
+    // the north player would have paid double if the winner is the dealer
+    let north_share = if winner_is_dealer { basic_points * 2 } else { basic_points };
+    let north_bisection = if game.rules.sanma == Some(SanmaTsumoPayment::NorthBisection)
+    {   north_share / 2   }
+    else
+    {   0   };
+
+    for (i, player) in game.players.iter().enumerate()
+    {
+        if i == winning_player_idx
+        {   continue;   }
+
+        let payment = if winner_is_dealer || player.seat_wind == SuitVal::East
+        {   utils::round_up_to_100(basic_points * 2 + north_bisection)   }
+        else
+        {   utils::round_up_to_100(basic_points + north_bisection)       };
+        // honba are always 100 points from each payer for tsumo, so there's less to go around in sanma
+        let payment = payment + honba_points / (NUM_PLAYERS as i32 - 1);
+
+        hand_score.point_changes[i] -= payment;
+        hand_score.point_changes[winning_player_idx] += payment;
+    }
+}
+
+/// Scores nagashi mangan at an exhaustive draw. It's paid as a mangan tsumo, without honba or riichi sticks
+pub fn score_nagashi_mangan(game : &Game, player_idx : usize) -> HandScore
+{
+    const MANGAN_HAN : usize = 5;
+    const MANGAN_BASIC_POINTS : usize = 2000;
+
+    let mut hand_score = HandScore {
+        hand_yaku : vec![(YakuType::NagashiMangan, MANGAN_HAN)],
+        dora : 0,
+        aka_dora : 0,
+        ura_dora : 0,
+        han : MANGAN_HAN,
+        fu : 0,
+        fu_components : vec![],
+        limit : HandLimit::Mangan,
+        basic_points : MANGAN_BASIC_POINTS,
+        honba : 0,
+        riichi_sticks : 0,
+        point_changes : vec![0; game.num_players()],
+        hand_interpretation : None,
+    };
+
+    add_tsumo_payments(game, &mut hand_score, player_idx, 0);
+
+    hand_score
+}
+
+/// rounds fu up to the next 10. Chiitoitsu's 25 fu is the only value that isn't rounded
+pub fn round_up_fu(fu : usize) -> usize
+{
+    if fu == 25 || fu.is_multiple_of(10)
+    {   fu  }
+    else
+    {   fu + 10 - (fu % 10)   }
+}
+
+
+pub fn score_points(game : &mut Game, winning_player_idx : Option<usize>)
+    {
+        let noten_payment_points = game.rules.noten_payment_points;
         match winning_player_idx
         {
             None => {
+                let nagashi_mangan_idxs : Vec<usize> = if game.rules.nagashi_mangan == NagashiManganRule::Disabled
+                {   vec![]   }
+                else
+                {   (0..game.num_players()).filter(|player_idx| game.players[*player_idx].is_nagashi_mangan()).collect()   };
+
+                for player_idx in &nagashi_mangan_idxs
+                {
+                    let hand_score = score_nagashi_mangan(game, *player_idx);
+
+                    tui_output::output_player_win_or_lose(&game.players[*player_idx], &hand_score, game.human_is_playing);
+
+                    for (player, point_change) in game.players.iter_mut().zip(hand_score.point_changes)
+                    {
+                        player.points += point_change;
+                    }
+                }
+
+                if ! nagashi_mangan_idxs.is_empty() && game.rules.nagashi_mangan == NagashiManganRule::InsteadOfNotenPayments
+                {  return;  }
+
                 let num_tenpai_players = game.players.iter().filter(|player| player.tenpai).count();
+                let num_players = game.num_players();
 
                 // no one or everyone is in tenpai
-                if num_tenpai_players == 0 || num_tenpai_players == 4
+                if num_tenpai_players == 0 || num_tenpai_players == num_players
                 {  return;  }
 
                 for player in &mut game.players {
                     if player.tenpai
                     {
-                        player.points += utils::round_up_to_100(EXHAUSTIVE_DRAW_POINTS / (num_tenpai_players as i32));
+                        player.points += utils::round_up_to_100(noten_payment_points / (num_tenpai_players as i32));
                     }
                     else
                     {
-                        player.points -= utils::round_up_to_100(EXHAUSTIVE_DRAW_POINTS / ((NUM_PLAYERS - num_tenpai_players) as i32));
+                        player.points -= utils::round_up_to_100(noten_payment_points / ((num_players - num_tenpai_players) as i32));
                     }
                 }
-
-                // rotate seats if dealer wasn't in tenpai and someone else was
-                if ! game.players.iter().find(|player| player.seat_wind == SuitVal::East).unwrap().tenpai
-                {
-                    for player in &mut game.players { player.rotate_wind(); }
-                }
-            }
-            Some(winning_player_idx) => {
-
-                let basic_points = game.players[winning_player_idx].score_hand_basic_points(game);
-
-                let winners_seat_wind : SuitVal = game.players[winning_player_idx].seat_wind;
-
-                let winning_player = &mut game.players[winning_player_idx];
-                let other_players = game.players.iter().filter(|player| player.seat_wind != game.players[winning_player_idx].seat_wind);
-
-                match game.players[winning_player_idx].ron_or_tsumo {
-                    WinningMethod::Ron(victim_index) =>  // "victim" is the player who got ron called on them
-                        if game.players[winning_player_idx].seat_wind == SuitVal::East
-                        {
-                            game.players[victim_index].points -= round_up_to_100((basic_points * 6) as i32);
-                            game.players[winning_player_idx].points += round_up_to_100((basic_points * 6) as i32);
-                        }
-                        else
-                        {
-                            game.players[victim_index].points -= round_up_to_100((basic_points * 4) as i32);
-                            game.players[winning_player_idx].points += round_up_to_100((basic_points * 4) as i32);
-                        },
-                    WinningMethod::Tsumo =>
-                        if game.players[winning_player_idx].seat_wind == SuitVal::East
-                        {
-                            for player in &mut game.players{
-                                if player.seat_wind != winners_seat_wind
-                                {
-                                    player.points -= round_up_to_100((basic_points * 2) as i32);
-                                }
-                            }
-
-                            game.players[winning_player_idx].points += round_up_to_100((basic_points * 2) as i32) * (NUM_PLAYERS -1) as i32;
-                        }
-                        else
-                        {
-                            for player in &mut game.players {
-                                if player.seat_wind == SuitVal::East
-                                {
-                                    player.points -= round_up_to_100((basic_points * 2) as i32);
-                                }
-                                else if player.seat_wind != winners_seat_wind
-                                {
-                                    player.points  -= round_up_to_100(basic_points as i32);
-
-                                }
-
-                            }
-
-                            game.players[winning_player_idx].points += round_up_to_100((basic_points * 2) as i32);
-                            game.players[winning_player_idx].points += round_up_to_100((basic_points * 2) as i32) * 2;
-
-                        },
-                    _ => panic!("Player won, but did not have ron or tsumo set"),
-                }
             }
+            Some(winning_player_idx) => pay_winner(game, winning_player_idx, game.honba),
         }
-    }
\ No newline at end of file
+    }
+
+/// Scores each player who won by ron on the same discard, in turn order from the discarder. Each winner is paid separately,
+/// but only the first gets the honba and riichi sticks
+pub fn score_ron_winners(game : &mut Game, winner_idxs : &[usize])
+{
+    for (i, winner_idx) in winner_idxs.iter().enumerate()
+    {
+        let honba = if i == 0 { game.honba } else { 0 };
+        pay_winner(game, *winner_idx, honba);
+    }
+}
+
+fn pay_winner(game : &mut Game, winning_player_idx : usize, honba : usize)
+{
+    let hand_score = score_hand_with_honba(game, winning_player_idx, honba);
+
+    tui_output::output_player_win_or_lose(&game.players[winning_player_idx], &hand_score, game.human_is_playing);
+
+    for (player, point_change) in game.players.iter_mut().zip(hand_score.point_changes)
+    {
+        player.points += point_change;
+    }
+
+    // the winner took every riichi stick. With multiple winners, the first in turn order gets them
+    game.riichi_sticks = 0;
+}
+
+/// A player's standing at the end of the game
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FinalResult {
+    pub player_idx : usize,
+    /// 1 for first place
+    pub placement : usize,
+    /// points at the end of the game. First place also takes any riichi sticks left on the table
+    pub raw_score : i32,
+    /// raw score minus the return points
+    pub return_score : i32,
+    /// the difference between the return points and the starting points of every player. Only first place gets it
+    pub oka : i32,
+    pub uma : i32,
+    /// return score plus oka and uma. Final scores add up to 0 and are usually shown in thousands
+    pub final_score : i32,
+}
+
+/// final standings ordered from first place to last
+pub fn final_results(game : &Game) -> Vec<FinalResult>
+{
+    let num_players = game.num_players();
+    let uma = game.rules.uma.by_placement(num_players);
+    let oka = (game.rules.return_points - game.rules.starting_points) * num_players as i32;
+
+    game.placements().iter().enumerate().map(|(placement, player_idx)| {
+        let mut raw_score = game.players[*player_idx].points;
+        let mut player_oka = 0;
+        if placement == 0
+        {
+            raw_score += RIICHI_STICK_POINTS * game.riichi_sticks as i32;
+            player_oka = oka;
+        }
+
+        let return_score = raw_score - game.rules.return_points;
+        let player_uma = uma[placement] * 1000;
+
+        FinalResult {
+            player_idx : *player_idx,
+            placement : placement + 1,
+            raw_score,
+            return_score,
+            oka : player_oka,
+            uma : player_uma,
+            final_score : return_score + player_oka + player_uma,
+        }
+    }).collect()
+}
@@ -0,0 +1,308 @@
+use crate::mahjong::*;
+
+/// shanten of a complete hand. Tenpai is 0
+pub const COMPLETE_HAND_SHANTEN : i32 = -1;
+
+/// number of sets in a complete hand, not counting the pair
+const NUM_HAND_SETS : usize = 4;
+
+/// The number of tiles a hand needs to change before it's tenpai. Tile counts are indexed by Tile::kind_idx,
+/// and every called set (including kans) counts as a finished set. The lowest of the standard form, chiitoitsu and kokushi
+pub fn shanten(counts : &[u8; NUM_TILE_KINDS], num_called_sets : usize) -> i32
+{
+    let mut shanten = standard_shanten(counts, num_called_sets);
+
+    // seven pairs and thirteen orphans are only possible with a closed hand
+    if num_called_sets == 0
+    {
+        shanten = shanten.min(chiitoitsu_shanten(counts)).min(kokushi_shanten(counts));
+    }
+
+    shanten
+}
+
+/// shanten for four sets and a pair
+pub fn standard_shanten(counts : &[u8; NUM_TILE_KINDS], num_called_sets : usize) -> i32
+{
+    let mut counts = *counts;
+    let mut best_shanten = 8 - 2 * num_called_sets as i32;
+
+    // with no pair picked out yet, then with each possible pair
+    search_sets(&mut counts, 0, num_called_sets, 0, false, &mut best_shanten);
+    for kind_idx in 0..NUM_TILE_KINDS
+    {
+        if counts[kind_idx] >= 2
+        {
+            counts[kind_idx] -= 2;
+            search_sets(&mut counts, 0, num_called_sets, 0, true, &mut best_shanten);
+            counts[kind_idx] += 2;
+        }
+    }
+
+    best_shanten
+}
+
+/// removes every combination of sets and partial sets (two tiles a set is waiting on) from the tiles, starting from kind_idx.
+/// Each finished set lowers shanten by 2, and each partial set or the pair lowers it by 1.
+/// A hand can only use as many partial sets as it has room for sets
+fn search_sets(counts : &mut [u8; NUM_TILE_KINDS], kind_idx : usize, num_sets : usize, num_partial_sets : usize, has_pair : bool, best_shanten : &mut i32)
+{
+    let kind_idx = match (kind_idx..NUM_TILE_KINDS).find(|idx| counts[*idx] > 0) {
+        Some(kind_idx) => kind_idx,
+        None => {
+            let num_partial_sets = num_partial_sets.min(NUM_HAND_SETS - num_sets.min(NUM_HAND_SETS));
+            let shanten = 8 - 2 * num_sets as i32 - num_partial_sets as i32 - has_pair as i32;
+            *best_shanten = (*best_shanten).min(shanten);
+            return;
+        }
+    };
+
+    let is_numbered = kind_idx < 27;
+    let number_idx = kind_idx % 9;
+
+    if counts[kind_idx] >= 3
+    {
+        counts[kind_idx] -= 3;
+        search_sets(counts, kind_idx, num_sets + 1, num_partial_sets, has_pair, best_shanten);
+        counts[kind_idx] += 3;
+    }
+
+    if is_numbered && number_idx <= 6 && counts[kind_idx + 1] > 0 && counts[kind_idx + 2] > 0
+    {
+        counts[kind_idx] -= 1;
+        counts[kind_idx + 1] -= 1;
+        counts[kind_idx + 2] -= 1;
+        search_sets(counts, kind_idx, num_sets + 1, num_partial_sets, has_pair, best_shanten);
+        counts[kind_idx] += 1;
+        counts[kind_idx + 1] += 1;
+        counts[kind_idx + 2] += 1;
+    }
+
+    // partial sets past the number of sets the hand has room for can't lower shanten
+    if num_sets + num_partial_sets < NUM_HAND_SETS
+    {
+        if counts[kind_idx] >= 2
+        {
+            counts[kind_idx] -= 2;
+            search_sets(counts, kind_idx, num_sets, num_partial_sets + 1, has_pair, best_shanten);
+            counts[kind_idx] += 2;
+        }
+
+        // two sided or edge waits, then closed waits
+        for gap in [1, 2]
+        {
+            if is_numbered && number_idx + gap <= 8 && counts[kind_idx + gap] > 0
+            {
+                counts[kind_idx] -= 1;
+                counts[kind_idx + gap] -= 1;
+                search_sets(counts, kind_idx, num_sets, num_partial_sets + 1, has_pair, best_shanten);
+                counts[kind_idx] += 1;
+                counts[kind_idx + gap] += 1;
+            }
+        }
+    }
+
+    // leave the rest of this tile as isolated tiles
+    let num_isolated = counts[kind_idx];
+    counts[kind_idx] = 0;
+    search_sets(counts, kind_idx + 1, num_sets, num_partial_sets, has_pair, best_shanten);
+    counts[kind_idx] = num_isolated;
+}
+
+/// shanten for seven different pairs
+pub fn chiitoitsu_shanten(counts : &[u8; NUM_TILE_KINDS]) -> i32
+{
+    let num_pairs = counts.iter().filter(|count| **count >= 2).count() as i32;
+    let num_kinds = counts.iter().filter(|count| **count > 0).count() as i32;
+
+    // four of a tile is still only one pair, so a hand short on different tiles needs more changes
+    6 - num_pairs + (7 - num_kinds).max(0)
+}
+
+/// shanten for thirteen orphans: one of every terminal and honor, plus a pair of one of them
+pub fn kokushi_shanten(counts : &[u8; NUM_TILE_KINDS]) -> i32
+{
+    let terminal_and_honor_counts : Vec<u8> = (0..NUM_TILE_KINDS)
+        .filter(|kind_idx| Tile::from_kind_idx(*kind_idx).is_honor_or_terminal())
+        .map(|kind_idx| counts[kind_idx])
+        .collect();
+
+    let num_kinds = terminal_and_honor_counts.iter().filter(|count| **count > 0).count() as i32;
+    let has_pair = terminal_and_honor_counts.iter().any(|count| *count >= 2);
+
+    13 - num_kinds - has_pair as i32
+}
+
+/// A discard and the tiles that would lower the hand's shanten after it (ukeire)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiscardUkeire {
+    /// index in the hand of the first tile of this kind
+    pub discard_idx : usize,
+    pub discard : Tile,
+    /// shanten after the discard
+    pub shanten : i32,
+    /// each tile that would lower shanten, with how many copies of it haven't been seen
+    pub accepted_tiles : Vec<(Tile, usize)>,
+}
+
+impl DiscardUkeire {
+    /// total unseen copies of every accepted tile
+    pub fn num_accepted(&self) -> usize
+    {
+        self.accepted_tiles.iter().map(|(_, num_left)| num_left).sum()
+    }
+}
+
+/// tiles that would lower the shanten of a hand waiting on a draw, with how many copies of each are left.
+/// seen_counts are tiles that can be seen outside of the hand, such as discards, called sets and dora indicators
+pub fn ukeire(counts : &[u8; NUM_TILE_KINDS], num_called_sets : usize, seen_counts : &[u8; NUM_TILE_KINDS]) -> Vec<(Tile, usize)>
+{
+    let current_shanten = shanten(counts, num_called_sets);
+    let mut counts = *counts;
+    let mut accepted_tiles = vec![];
+
+    for kind_idx in 0..NUM_TILE_KINDS
+    {
+        if counts[kind_idx] >= 4
+        {   continue;   }
+
+        counts[kind_idx] += 1;
+        let shanten_after_draw = shanten(&counts, num_called_sets);
+        counts[kind_idx] -= 1;
+
+        if shanten_after_draw < current_shanten
+        {
+            let num_left = 4u8.saturating_sub(counts[kind_idx] + seen_counts[kind_idx]);
+            accepted_tiles.push((Tile::from_kind_idx(kind_idx), num_left as usize));
+        }
+    }
+
+    accepted_tiles
+}
+
+/// ukeire after each different discard from a hand that just drew a tile. Red fives are their own discard
+pub fn discard_ukeire(hand : &[Tile], num_called_sets : usize, seen_counts : &[u8; NUM_TILE_KINDS]) -> Vec<DiscardUkeire>
+{
+    let mut counts = tile_counts(hand);
+    let mut discards = vec![];
+
+    for (discard_idx, discard) in hand.iter().enumerate()
+    {
+        let already_considered = hand[..discard_idx].iter().any(|tile| *tile == *discard && tile.red == discard.red);
+        if already_considered
+        {   continue;   }
+
+        counts[discard.kind_idx()] -= 1;
+        discards.push(DiscardUkeire {
+            discard_idx,
+            discard : *discard,
+            shanten : shanten(&counts, num_called_sets),
+            accepted_tiles : ukeire(&counts, num_called_sets, seen_counts),
+        });
+        counts[discard.kind_idx()] += 1;
+    }
+
+    discards
+}
+
+
+#[test]
+fn test_shanten()
+{
+    let counts_of = |tiles : Vec<Tile>| tile_counts(&tiles);
+    let honor = |value : SuitVal| Tile { suit : Suit::Honor, value, red : false };
+
+    // 123m 456m 789m 234p 99s
+    let mut complete_hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::man_tile(7), Tile::man_tile(8), Tile::man_tile(9), Tile::pin_tile(2), Tile::pin_tile(3), Tile::pin_tile(4),
+        Tile::sou_tile(9), Tile::sou_tile(9),
+    ];
+    assert_eq!(shanten(&counts_of(complete_hand.clone()), 0), COMPLETE_HAND_SHANTEN);
+
+    complete_hand[11] = honor(SuitVal::East);
+    assert_eq!(shanten(&counts_of(complete_hand.clone()), 0), 0);
+    complete_hand.remove(11);
+    assert_eq!(shanten(&counts_of(complete_hand.clone()), 0), 0);
+
+    // 147m 258p 369s ESWN
+    let unconnected_hand = vec![
+        Tile::man_tile(1), Tile::man_tile(4), Tile::man_tile(7), Tile::pin_tile(2), Tile::pin_tile(5), Tile::pin_tile(8),
+        Tile::sou_tile(3), Tile::sou_tile(6), Tile::sou_tile(9),
+        honor(SuitVal::East), honor(SuitVal::South), honor(SuitVal::West), honor(SuitVal::North),
+    ];
+    assert_eq!(standard_shanten(&counts_of(unconnected_hand.clone()), 0), 8);
+    assert_eq!(chiitoitsu_shanten(&counts_of(unconnected_hand.clone())), 6);
+    assert_eq!(kokushi_shanten(&counts_of(unconnected_hand.clone())), 7);
+    assert_eq!(shanten(&counts_of(unconnected_hand), 0), 6);
+
+    // six pairs and a single tile is tenpai for chiitoitsu
+    let pairs_hand = vec![
+        Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(5), Tile::man_tile(5), Tile::pin_tile(3), Tile::pin_tile(3),
+        Tile::pin_tile(8), Tile::pin_tile(8), Tile::sou_tile(4), Tile::sou_tile(4), honor(SuitVal::Red), honor(SuitVal::Red),
+        honor(SuitVal::North),
+    ];
+    assert_eq!(chiitoitsu_shanten(&counts_of(pairs_hand.clone())), 0);
+    assert_eq!(shanten(&counts_of(pairs_hand), 0), 0);
+
+    // four of a kind only counts once towards seven pairs
+    let four_of_a_kind_hand = vec![
+        Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(1), Tile::pin_tile(3), Tile::pin_tile(3),
+        Tile::pin_tile(8), Tile::pin_tile(8), Tile::sou_tile(4), Tile::sou_tile(4), honor(SuitVal::Red), honor(SuitVal::Red),
+        honor(SuitVal::North),
+    ];
+    assert_eq!(chiitoitsu_shanten(&counts_of(four_of_a_kind_hand)), 2);
+
+    // thirteen different terminals and honors is tenpai on any of them
+    let kokushi_hand : Vec<Tile> = all_tile_kinds().into_iter().filter(|tile| tile.is_honor_or_terminal()).collect();
+    assert_eq!(shanten(&counts_of(kokushi_hand.clone()), 0), 0);
+    let mut complete_kokushi_hand = kokushi_hand;
+    complete_kokushi_hand.push(Tile::man_tile(1));
+    assert_eq!(shanten(&counts_of(complete_kokushi_hand), 0), COMPLETE_HAND_SHANTEN);
+
+    // two called sets, with 123m, 45p, 99s and an east left in the hand
+    let called_hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::pin_tile(4), Tile::pin_tile(5),
+        Tile::sou_tile(9), Tile::sou_tile(9), honor(SuitVal::East),
+    ];
+    assert_eq!(shanten(&counts_of(called_hand.clone()), 2), 0);
+    assert_eq!(shanten(&counts_of(called_hand[..7].to_vec()), 2), 0);
+
+    // more partial sets than the hand has room for don't help
+    let partial_sets_hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(4), Tile::man_tile(5), Tile::pin_tile(1), Tile::pin_tile(2),
+        Tile::pin_tile(4), Tile::pin_tile(5), Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(4), Tile::sou_tile(5),
+        honor(SuitVal::East),
+    ];
+    assert_eq!(standard_shanten(&counts_of(partial_sets_hand), 0), 4);
+}
+
+#[test]
+fn test_ukeire()
+{
+    // 23456m 789p 123s 99s after drawing a west, with two 1m seen elsewhere
+    let hand = vec![
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::pin_tile(7), Tile::pin_tile(8), Tile::pin_tile(9), Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3),
+        Tile::sou_tile(9), Tile::sou_tile(9), Tile { suit : Suit::Honor, value : SuitVal::West, red : false },
+    ];
+    let seen_counts = tile_counts(&[Tile::man_tile(1), Tile::man_tile(1), Tile::sou_tile(9)]);
+
+    let discards = discard_ukeire(&hand, 0, &seen_counts);
+    assert_eq!(discards.len(), 13);
+
+    let west_discard = discards.iter().find(|discard| discard.discard.suit == Suit::Honor).unwrap();
+    assert_eq!(west_discard.discard_idx, 13);
+    assert_eq!(west_discard.shanten, 0);
+    // 23456m waits on 1m, 4m or 7m
+    assert_eq!(west_discard.accepted_tiles, vec![(Tile::man_tile(1), 2), (Tile::man_tile(4), 3), (Tile::man_tile(7), 4)]);
+    assert_eq!(west_discard.num_accepted(), 9);
+    assert!(discards.iter().all(|discard| discard.shanten >= west_discard.shanten));
+
+    // the same kind of tile is only considered once
+    let mut hand = hand;
+    hand[13] = Tile::man_tile(2);
+    let discards = discard_ukeire(&hand, 0, &seen_counts);
+    assert_eq!(discards.len(), 12);
+    assert_eq!(discards.iter().filter(|discard| discard.discard == Tile::man_tile(2)).count(), 1);
+}
@@ -0,0 +1,420 @@
+use rand::rngs::StdRng;
+
+use crate::mahjong::*;
+
+/// Makes a computer player's decisions. A player uses the strategy set with Game::set_player_strategy, or else their ai_algorithm.
+/// Every decision is made looking at the game from the player's seat, and is only asked for when there's a legal choice to make
+pub trait AIStrategy {
+    /// index into view.hand() of the tile to discard. The tile just drawn is the last one in the hand
+    fn choose_discard(&mut self, view : &SeatView, rng : &mut StdRng) -> usize;
+
+    /// one of possible_calls to make on a discarded tile, or on a tile added to a kan, or None to let it go.
+    /// Ron is one of the possible calls whenever the player can win on the tile
+    fn choose_call(&mut self, view : &SeatView, tile : Tile, possible_calls : &[CalledSet], rng : &mut StdRng) -> Option<CalledSet>;
+
+    /// asked after choosing a discard whenever the player can declare riichi. Returns one of tenpai_discard_idxs to discard while
+    /// declaring riichi, which doesn't have to be the discard already chosen, or None to discard the chosen tile without riichi
+    fn declare_riichi(&mut self, view : &SeatView, discard_idx : usize, tenpai_discard_idxs : &[usize]) -> Option<usize>
+    {   None    }
+
+    /// one of kan_tiles to declare a closed kan with, or add to a pon, or None to carry on with the turn
+    fn declare_kan(&mut self, view : &SeatView, kan_tiles : &[Tile]) -> Option<Tile>
+    {   None    }
+
+    /// asked whenever the tile just drawn completes the hand. True to win with tsumo
+    fn declare_tsumo(&mut self, view : &SeatView) -> bool
+    {   true    }
+
+    /// asked when the player can end the hand with nine different terminals and honors on their first draw
+    fn declare_kyuushu_kyuuhai(&mut self, view : &SeatView) -> bool
+    {   false   }
+}
+
+/// The game as one seat sees it: their own hand, and everything on the table. Other players' hands and the wall stay hidden
+pub struct SeatView<'a> {
+    game : &'a Game,
+    seat : usize,
+}
+
+/// a player as seen from across the table. Everything but their hand
+pub struct OpponentView<'a> {
+    player : &'a Player,
+}
+
+impl<'a> SeatView<'a> {
+    pub fn new(game : &'a Game, seat : usize) -> Self
+    {
+        SeatView { game, seat }
+    }
+
+    /// index of the seat in the game's players, which doesn't change as seat winds rotate
+    pub fn seat(&self) -> usize
+    {
+        self.seat
+    }
+
+    /// the player in this seat
+    pub fn player(&self) -> &'a Player
+    {
+        &self.game.players[self.seat]
+    }
+
+    pub fn hand(&self) -> &'a [Tile]
+    {
+        &self.player().hand
+    }
+
+    /// the player in any other seat
+    pub fn opponent(&self, seat : usize) -> OpponentView<'a>
+    {
+        assert_ne!(seat, self.seat, "A seat can see its own hand, so it isn't its own opponent");
+        OpponentView { player : &self.game.players[seat] }
+    }
+
+    /// every other seat, in turn order from this one
+    pub fn opponent_seats(&self) -> impl Iterator<Item = usize>
+    {
+        let num_players = self.num_players();
+        let seat = self.seat;
+        (1..num_players).map(move |offset| (seat + offset) % num_players)
+    }
+
+    /// the seat whose turn it is. During a call this is whoever discarded the tile
+    pub fn current_seat(&self) -> usize
+    {
+        self.game.curr_player_idx
+    }
+
+    pub fn num_players(&self) -> usize
+    {
+        self.game.num_players()
+    }
+
+    pub fn rules(&self) -> &'a RuleSet
+    {
+        &self.game.rules
+    }
+
+    pub fn is_sanma(&self) -> bool
+    {
+        self.game.is_sanma()
+    }
+
+    pub fn round_wind(&self) -> SuitVal
+    {
+        self.game.round_wind
+    }
+
+    pub fn honba(&self) -> usize
+    {
+        self.game.honba
+    }
+
+    pub fn riichi_sticks(&self) -> usize
+    {
+        self.game.riichi_sticks
+    }
+
+    pub fn dora_indicators(&self) -> Vec<Tile>
+    {
+        self.game.dora_indicators()
+    }
+
+    /// the tiles the dora indicators point to, once for each indicator
+    pub fn dora(&self) -> Vec<Tile>
+    {
+        self.dora_indicators().iter().map(|indicator| self.game.dora_from_indicator(*indicator)).collect()
+    }
+
+    /// true for a dragon, this seat's wind, or the round wind
+    pub fn is_yakuhai_tile(&self, tile : Tile) -> bool
+    {
+        scoring::is_yakuhai_tile(self.player(), self.game, tile)
+    }
+
+    /// true if the game would end with this seat winning the hand, and every seat left with the points at its index
+    pub fn game_ends_after_win(&self, points_after : &[i32]) -> bool
+    {
+        let is_dealer = self.player().seat_wind == SuitVal::East;
+        let hand_result = if is_dealer { RepeatHand::DealerWon } else { RepeatHand::RotateWinds };
+
+        self.game.game_is_over_with_points(points_after, hand_result, ! is_dealer)
+    }
+
+    pub fn tiles_left_in_live_wall(&self) -> usize
+    {
+        self.game.tiles_left_in_live_wall()
+    }
+
+    /// how many of each tile kind are on the table for everyone to see
+    pub fn visible_tile_counts(&self) -> [u8; NUM_TILE_KINDS]
+    {
+        self.game.visible_tile_counts()
+    }
+
+    /// how many copies of each tile kind this seat can't see. They're in the other hands, or still in the wall
+    pub fn unseen_tile_counts(&self) -> [u8; NUM_TILE_KINDS]
+    {
+        let mut seen_counts = self.visible_tile_counts();
+        for tile in self.hand()
+        {   seen_counts[tile.kind_idx()] += 1;   }
+
+        let mut unseen_counts = [0; NUM_TILE_KINDS];
+        for tile in all_tile_kinds()
+        {
+            // there's no 2 to 8 man in sanma
+            if self.game.is_sanma() && tile.suit == Suit::Man && ! tile.is_terminal()
+            {   continue;   }
+
+            unseen_counts[tile.kind_idx()] = 4u8.saturating_sub(seen_counts[tile.kind_idx()]);
+        }
+
+        unseen_counts
+    }
+
+    /// shanten and ukeire after each discard from this seat's hand, best first
+    pub fn discard_ukeire(&self) -> Vec<DiscardUkeire>
+    {
+        self.game.player_discard_ukeire(self.seat)
+    }
+
+    /// basic points this seat would score by calling ron on the current player's tile, without the hidden ura dora.
+    /// 0 if the win has no yaku
+    pub fn ron_basic_points(&self, ron_tile : Tile, ron_call : &CalledSet) -> usize
+    {
+        let mut winner = self.player().clone();
+        winner.open_tiles_with_call(ron_tile, ron_call.clone());
+        winner.last_picked_tile = ron_tile;
+        winner.ron_or_tsumo = WinningMethod::Ron(self.current_seat());
+
+        scoring::evaluate_hand_without_ura_dora(&winner, self.game).basic_points
+    }
+
+    /// Basic points this seat would score by winning with tsumo on a later draw, if its concealed tiles were hand, with the winning
+    /// tile last. 0 if the hand isn't complete or has no yaku. Ura dora are hidden until someone wins, so they're left out, and so
+    /// are ippatsu and rinshan kaihou, which only this turn's draw could win with
+    pub fn tsumo_basic_points(&self, hand : &[Tile]) -> usize
+    {
+        let mut winner = self.player().clone();
+        winner.hand = hand.to_vec();
+        winner.last_picked_tile = *hand.last().expect("A winning hand has tiles");
+        winner.sort_hand();
+        winner.ron_or_tsumo = WinningMethod::Tsumo;
+        winner.iipatsu = false;
+
+        // the game still remembers whether this turn's draw was a kan replacement tile
+        let hand_score = scoring::evaluate_hand_without_ura_dora(&winner, self.game);
+        let rinshan_han : usize = hand_score.hand_yaku.iter()
+            .filter(|(yaku_type, _)| *yaku_type == YakuType::RinshanKaihou)
+            .map(|(_, han)| han)
+            .sum();
+        if rinshan_han == 0 || hand_score.hand_interpretation.is_none()
+        {   return hand_score.basic_points;   }
+
+        // rinshan kaihou was the only yaku
+        if hand_score.hand_yaku.len() == 1
+        {   return 0;   }
+
+        scoring::basic_points_from_han_and_fu(hand_score.han - rinshan_han, hand_score.fu)
+    }
+}
+
+impl<'a> OpponentView<'a> {
+    pub fn discard_pile(&self) -> &'a [Tile]
+    {
+        &self.player.discard_pile
+    }
+
+    /// tiles this player discarded which others called
+    pub fn tiles_others_called(&self) -> &'a [Tile]
+    {
+        &self.player.tiles_others_called
+    }
+
+    pub fn called_sets(&self) -> &'a [CalledSet]
+    {
+        &self.player.called_sets
+    }
+
+    pub fn num_hand_tiles(&self) -> usize
+    {
+        self.player.hand.len()
+    }
+
+    pub fn is_in_riichi(&self) -> bool
+    {
+        self.player.is_in_riichi()
+    }
+
+    pub fn points(&self) -> i32
+    {
+        self.player.points
+    }
+
+    pub fn seat_wind(&self) -> SuitVal
+    {
+        self.player.seat_wind
+    }
+
+    pub fn nuki_dora(&self) -> usize
+    {
+        self.player.nuki_dora
+    }
+
+    /// true if the player is in riichi, or has called enough sets to probably be tenpai
+    pub fn is_threat(&self) -> bool
+    {
+        defense::is_threat(self.player)
+    }
+
+    /// how likely the player is to be waiting on a tile, from 0 for genbutsu upwards. See defense::tile_danger
+    pub fn tile_danger(&self, tile : Tile, seen_counts : &[u8; NUM_TILE_KINDS]) -> u32
+    {
+        defense::tile_danger(tile, self.player, seen_counts)
+    }
+}
+
+/// the built in strategies
+impl AIStrategy for AIAlgorithm {
+    fn choose_discard(&mut self, view : &SeatView, rng : &mut StdRng) -> usize
+    {
+        let player = view.player();
+
+        match self {
+            AIAlgorithm::DumbAsBricks => 0,
+            AIAlgorithm::SimpleDiscardAlwaysCall => player.simple_discard_idx(rng),
+            AIAlgorithm::EfficientDiscardAlwaysCall => player.efficient_discard_idx(view),
+            AIAlgorithm::DefensiveDiscardAlwaysCall | AIAlgorithm::DefensiveDiscardSmartCall => player.defensive_discard_idx(view),
+            AIAlgorithm::MonteCarloDiscardSmartCall(settings) => monte_carlo::monte_carlo_discard_idx(view, settings, rng),
+        }
+    }
+
+    fn choose_call(&mut self, view : &SeatView, tile : Tile, possible_calls : &[CalledSet], rng : &mut StdRng) -> Option<CalledSet>
+    {
+        match self {
+            AIAlgorithm::DumbAsBricks => None,
+
+            AIAlgorithm::DefensiveDiscardSmartCall | AIAlgorithm::MonteCarloDiscardSmartCall(_) => view.player().smart_call(view, tile)
+                .filter(|call| possible_calls.contains(call)),
+
+            // ron, then kan, then pon, then the first chii
+            AIAlgorithm::SimpleDiscardAlwaysCall | AIAlgorithm::EfficientDiscardAlwaysCall | AIAlgorithm::DefensiveDiscardAlwaysCall => {
+                possible_calls.iter()
+                    .min_by_key(|call| match call.call_type {
+                        CallTypes::Ron(_) => 0,
+                        CallTypes::OpenKan => 1,
+                        CallTypes::Pon => 2,
+                        _ => 3,
+                    })
+                    .cloned()
+            }
+        }
+    }
+
+    /// keeps the chosen discard if it's already tenpai. The defensive and Monte Carlo AIs won't give up the tile they chose to riichi
+    fn declare_riichi(&mut self, view : &SeatView, discard_idx : usize, tenpai_discard_idxs : &[usize]) -> Option<usize>
+    {
+        match self {
+            AIAlgorithm::DumbAsBricks => None,
+            _ if tenpai_discard_idxs.contains(&discard_idx) => Some(discard_idx),
+            AIAlgorithm::DefensiveDiscardAlwaysCall | AIAlgorithm::DefensiveDiscardSmartCall | AIAlgorithm::MonteCarloDiscardSmartCall(_) => None,
+            _ => Some(tenpai_discard_idxs[0]),
+        }
+    }
+
+    fn declare_tsumo(&mut self, view : &SeatView) -> bool
+    {
+        *self != AIAlgorithm::DumbAsBricks
+    }
+
+    fn declare_kyuushu_kyuuhai(&mut self, view : &SeatView) -> bool
+    {
+        *self != AIAlgorithm::DumbAsBricks
+    }
+}
+
+
+#[test]
+fn test_custom_strategy()
+{
+    /// discards whatever it draws, never calls or wins, and kans whenever it can
+    struct Tsumogiri;
+
+    impl AIStrategy for Tsumogiri {
+        fn choose_discard(&mut self, view : &SeatView, rng : &mut StdRng) -> usize
+        {   view.hand().len() - 1   }
+
+        fn choose_call(&mut self, view : &SeatView, tile : Tile, possible_calls : &[CalledSet], rng : &mut StdRng) -> Option<CalledSet>
+        {   None    }
+
+        fn declare_kan(&mut self, view : &SeatView, kan_tiles : &[Tile]) -> Option<Tile>
+        {   kan_tiles.first().copied()  }
+
+        fn declare_tsumo(&mut self, view : &SeatView) -> bool
+        {   false   }
+    }
+
+    let east = Tile { suit : Suit::Honor, value : SuitVal::East, red : false };
+    let south = Tile { suit : Suit::Honor, value : SuitVal::South, red : false };
+    let west = Tile { suit : Suit::Honor, value : SuitVal::West, red : false };
+    let north = Tile { suit : Suit::Honor, value : SuitVal::North, red : false };
+
+    let hands = vec![
+        [2, 3, 4, 5, 6, 7, 8, 9].map(Tile::man_tile).into_iter().chain([1, 2, 3, 4, 5].map(Tile::pin_tile)).collect(),
+        [1, 1, 1, 1].map(Tile::man_tile).into_iter().chain([6, 7, 8, 9].map(Tile::pin_tile)).chain([1, 2, 3, 4, 5].map(Tile::sou_tile)).collect(),
+        [6, 7, 8, 9].map(Tile::sou_tile).into_iter().chain([east, south, west, north]).chain([2, 3, 4, 5, 6].map(Tile::man_tile)).collect(),
+        [4, 5, 6, 7, 8, 9].map(Tile::man_tile).into_iter().chain([2, 3, 4, 5, 6, 7, 8].map(Tile::pin_tile)).collect(),
+    ];
+
+    let mut game = Game::with_hands_and_draws(RuleSet::tenhou(), hands, vec![]);
+    for player_idx in 0..game.num_players()
+    {
+        game.set_player_strategy(player_idx, Box::new(Tsumogiri));
+    }
+
+    let hand_result = game.play_hand();
+
+    // no one won, and the only kan was the one dealt
+    assert!(hand_result == RepeatHand::DealerTenpai || hand_result == RepeatHand::DealerNoten);
+    assert!(game.players.iter().all(|player| ! player.is_human && player.ron_or_tsumo == WinningMethod::NotWonYet));
+    assert!(game.players[1].called_sets.len() == 1 && game.players[1].called_sets[0].call_type == CallTypes::ClosedKan);
+    assert!(game.players.iter().enumerate().all(|(player_idx, player)| player_idx == 1 || player.called_sets.is_empty()));
+}
+
+#[test]
+fn test_tsumo_basic_points()
+{
+    // riichi, tsumo, tanyao and pinfu, won on a later draw
+    let mut game = Game::with_seed(RuleSet::tenhou(), 0);
+    game.setup_for_hand();
+    let hand = vec![
+        Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(4), Tile::man_tile(4), Tile::man_tile(5), Tile::man_tile(6),
+        Tile::pin_tile(3), Tile::pin_tile(4), Tile::pin_tile(5), Tile::sou_tile(5), Tile::sou_tile(5),
+        Tile::sou_tile(6), Tile::sou_tile(7), Tile::sou_tile(8),
+    ];
+    game.players[1].riichi = true;
+    let basic_points = SeatView::new(&game, 1).tsumo_basic_points(&hand);
+    assert!(basic_points > 0);
+
+    // riichi this turn, or a kan replacement tile this turn, doesn't make the later draw ippatsu or rinshan kaihou
+    game.players[1].iipatsu = true;
+    game.drew_from_dead_wall = true;
+    assert_eq!(SeatView::new(&game, 1).tsumo_basic_points(&hand), basic_points);
+
+    // the ura dora stay hidden, whatever is under the dora indicator. The hand rons on 5s or 8s
+    game.players[1].hand = hand[..13].to_vec();
+    game.players[1].update_callable_tiles();
+    game.players[1].check_complete_hand_and_update_waits();
+    let ron_set = game.players[1].callable_tiles[&Tile::sou_tile(8)].ron_set.clone();
+    let ron_call = CalledSet { call_type : CallTypes::Ron(ron_set.set_type), set : ron_set };
+    let ron_basic_points = SeatView::new(&game, 1).ron_basic_points(Tile::sou_tile(8), &ron_call);
+    assert!(ron_basic_points > 0);
+
+    let ura_dora_indicator_idx = game.dora_indicators_idx() + 1;
+    for ura_dora_indicator in [Tile::sou_tile(4), Tile::sou_tile(7), Tile::man_tile(1)]
+    {
+        game.tiles[ura_dora_indicator_idx] = ura_dora_indicator;
+        assert_eq!(SeatView::new(&game, 1).tsumo_basic_points(&hand), basic_points);
+        assert_eq!(SeatView::new(&game, 1).ron_basic_points(Tile::sou_tile(8), &ron_call), ron_basic_points);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::mahjong::*;
+
+/// prefix of the shuffle seed in Tenhou's game logs
+const TENHOU_SEED_PREFIX : &str = "mt19937ar-sha512-n288-base64,";
+
+const MT_STATE_SIZE : usize = 624;
+const MT_SHIFT_SIZE : usize = 397;
+const MT_MATRIX_A : u32 = 0x9908b0df;
+const MT_UPPER_MASK : u32 = 0x80000000;
+const MT_LOWER_MASK : u32 = 0x7fffffff;
+
+/// number of random words drawn from the mersenne twister for each hand
+const NUM_RANDOM_WORDS : usize = 288;
+/// the random words are hashed in blocks of this many bytes
+const HASH_BLOCK_SIZE : usize = 128;
+
+/// The 32 bit mersenne twister (mt19937ar)
+#[derive(Clone, Debug)]
+pub struct Mt19937 {
+    state : Vec<u32>,
+    idx : usize,
+}
+
+impl Mt19937 {
+    pub fn new(seed : u32) -> Self
+    {
+        let mut state = vec![0; MT_STATE_SIZE];
+        state[0] = seed;
+        for i in 1..MT_STATE_SIZE
+        {
+            state[i] = 1812433253u32.wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30)).wrapping_add(i as u32);
+        }
+
+        Mt19937 { state, idx : MT_STATE_SIZE }
+    }
+
+    /// init_by_array from the reference implementation
+    pub fn from_key(key : &[u32]) -> Self
+    {
+        let mut mt = Mt19937::new(19650218);
+        let state = &mut mt.state;
+
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..MT_STATE_SIZE.max(key.len())
+        {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1664525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= MT_STATE_SIZE
+            {
+                state[0] = state[MT_STATE_SIZE - 1];
+                i = 1;
+            }
+            if j >= key.len()
+            {   j = 0;   }
+        }
+
+        for _ in 0..MT_STATE_SIZE - 1
+        {
+            state[i] = (state[i] ^ (state[i - 1] ^ (state[i - 1] >> 30)).wrapping_mul(1566083941)).wrapping_sub(i as u32);
+            i += 1;
+            if i >= MT_STATE_SIZE
+            {
+                state[0] = state[MT_STATE_SIZE - 1];
+                i = 1;
+            }
+        }
+
+        // the most significant bit is 1, so the initial state can't be all zeroes
+        state[0] = 0x80000000;
+
+        mt
+    }
+
+    fn twist(&mut self)
+    {
+        for i in 0..MT_STATE_SIZE
+        {
+            let y = (self.state[i] & MT_UPPER_MASK) | (self.state[(i + 1) % MT_STATE_SIZE] & MT_LOWER_MASK);
+            let mag = if y & 1 == 1 { MT_MATRIX_A } else { 0 };
+            self.state[i] = self.state[(i + MT_SHIFT_SIZE) % MT_STATE_SIZE] ^ (y >> 1) ^ mag;
+        }
+
+        self.idx = 0;
+    }
+
+    pub fn next_u32(&mut self) -> u32
+    {
+        if self.idx >= MT_STATE_SIZE
+        {   self.twist();   }
+
+        let mut y = self.state[self.idx];
+        self.idx += 1;
+
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c5680;
+        y ^= (y << 15) & 0xefc60000;
+        y ^ (y >> 18)
+    }
+}
+
+
+const SHA512_ROUND_CONSTANTS : [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+const SHA512_INITIAL_HASH : [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+pub fn sha512(bytes : &[u8]) -> [u8; 64]
+{
+    // the message is padded with a 1 bit, then zeroes, then its length in bits, to a multiple of 128 bytes
+    let mut message = bytes.to_vec();
+    message.push(0x80);
+    while message.len() % 128 != 112
+    {
+        message.push(0);
+    }
+    message.extend_from_slice(&((bytes.len() as u128) * 8).to_be_bytes());
+
+    let mut hash = SHA512_INITIAL_HASH;
+
+    for block in message.chunks(128)
+    {
+        let mut words = [0u64; 80];
+        for (i, word_bytes) in block.chunks(8).enumerate()
+        {
+            words[i] = u64::from_be_bytes(word_bytes.try_into().unwrap());
+        }
+        for i in 16..80
+        {
+            let s0 = words[i - 15].rotate_right(1) ^ words[i - 15].rotate_right(8) ^ (words[i - 15] >> 7);
+            let s1 = words[i - 2].rotate_right(19) ^ words[i - 2].rotate_right(61) ^ (words[i - 2] >> 6);
+            words[i] = words[i - 16].wrapping_add(s0).wrapping_add(words[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hash;
+        for i in 0..80
+        {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let choice = (e & f) ^ (! e & g);
+            let temp1 = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(SHA512_ROUND_CONSTANTS[i]).wrapping_add(words[i]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (hash_word, word) in hash.iter_mut().zip([a, b, c, d, e, f, g, h])
+        {
+            *hash_word = hash_word.wrapping_add(word);
+        }
+    }
+
+    let mut digest = [0u8; 64];
+    for (i, word) in hash.iter().enumerate()
+    {
+        digest[i * 8..(i + 1) * 8].copy_from_slice(&word.to_be_bytes());
+    }
+
+    digest
+}
+
+pub fn to_hex(bytes : &[u8]) -> String
+{
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// decodes standard base64. None if the string has any other characters
+fn base64_decode(encoded : &str) -> Option<Vec<u8>>
+{
+    const ALPHABET : &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut bytes = vec![];
+    let mut bits : u32 = 0;
+    let mut num_bits = 0;
+
+    for character in encoded.trim_end_matches('=').bytes()
+    {
+        let value = ALPHABET.iter().position(|alphabet_character| *alphabet_character == character)? as u32;
+        bits = (bits << 6) | value;
+        num_bits += 6;
+
+        if num_bits >= 8
+        {
+            num_bits -= 8;
+            bytes.push((bits >> num_bits) as u8);
+            bits &= (1 << num_bits) - 1;
+        }
+    }
+
+    Some(bytes)
+}
+
+/// Generates a wall for every hand of a Tenhou game from the game's seed, using Tenhou's published algorithm
+/// (mt19937ar-sha512-n288-base64). Walls can be replayed from a Tenhou log and checked against its records
+#[derive(Clone, Debug)]
+pub struct TenhouWall {
+    mt : Mt19937,
+}
+
+impl TenhouWall {
+    /// the seed is the base64 string from the SHUFFLE tag of a Tenhou log, with or without its "mt19937ar-sha512-n288-base64," prefix.
+    /// None if it isn't valid base64 of 624 words
+    pub fn from_seed(seed : &str) -> Option<Self>
+    {
+        let seed_bytes = base64_decode(seed.trim().trim_start_matches(TENHOU_SEED_PREFIX))?;
+        if seed_bytes.len() != MT_STATE_SIZE * 4
+        {   return None;   }
+
+        let key : Vec<u32> = seed_bytes.chunks(4).map(|word| u32::from_le_bytes(word.try_into().unwrap())).collect();
+
+        Some(TenhouWall { mt : Mt19937::from_key(&key) })
+    }
+
+    /// Tenhou's tile ids for the next hand, from yama[0] to yama[135], and the two dice.
+    /// Each id / 4 is the tile's Tile::kind_idx
+    pub fn next_hand(&mut self) -> (Vec<usize>, [u32; 2])
+    {
+        let mut random_bytes = vec![];
+        for _ in 0..NUM_RANDOM_WORDS
+        {
+            random_bytes.extend_from_slice(&self.mt.next_u32().to_le_bytes());
+        }
+
+        let mut random_words = vec![];
+        for block in random_bytes.chunks(HASH_BLOCK_SIZE)
+        {
+            let digest = sha512(block);
+            random_words.extend(digest.chunks(4).map(|word| u32::from_le_bytes(word.try_into().unwrap())));
+        }
+
+        let mut yama : Vec<usize> = (0..NUM_GAME_TILES).collect();
+        for (i, random_word) in random_words.iter().enumerate().take(NUM_GAME_TILES - 1)
+        {
+            let swap_idx = i + (*random_word as usize % (NUM_GAME_TILES - i));
+            yama.swap(i, swap_idx);
+        }
+
+        (yama, [random_words[135] % 6 + 1, random_words[136] % 6 + 1])
+    }
+}
+
+/// our tile for one of Tenhou's tile ids. The first red_fives_per_suit copies of each five are red
+pub fn tile_from_tenhou_id(tile_id : usize, red_fives_per_suit : usize) -> Tile
+{
+    let tile = Tile::from_kind_idx(tile_id / 4);
+
+    Tile { red : tile.value == SuitVal::Five && tile_id % 4 < red_fives_per_suit, ..tile }
+}
+
+
+#[test]
+fn test_mt19937()
+{
+    // first outputs of mt19937ar.out from the reference implementation
+    let mut mt = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+    assert_eq!([mt.next_u32(), mt.next_u32(), mt.next_u32()], [1067595299, 955945823, 477289528]);
+}
+
+#[test]
+fn test_sha512()
+{
+    assert_eq!(to_hex(&sha512(b"abc")),
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f");
+    assert_eq!(to_hex(&sha512(b"")),
+        "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e");
+}
+
+#[test]
+fn test_tenhou_wall()
+{
+    assert_eq!(base64_decode("TWFueQ=="), Some(b"Many".to_vec()));
+    assert_eq!(base64_decode("T!Fu"), None);
+    assert!(TenhouWall::from_seed("mt19937ar-sha512-n288-base64,TWFu").is_none());
+
+    // No published Tenhou log is at hand, so the expected tiles and dice below come from a separate implementation of the same
+    // algorithm instead of this one: CPython's random.seed, which is mt19937ar's init_by_array over the seed's little endian words,
+    // and hashlib's SHA-512. A test against a real SHUFFLE seed and its log's haipai would still be better
+    let seed_bytes : Vec<u8> = (0..MT_STATE_SIZE * 4).map(|i| ((i * 7 + 3) % 256) as u8).collect();
+    let key : Vec<u32> = seed_bytes.chunks(4).map(|word| u32::from_le_bytes(word.try_into().unwrap())).collect();
+    let mut wall = TenhouWall { mt : Mt19937::from_key(&key) };
+
+    // each hand uses the next 288 words from the twister
+    let (yama, dice) = wall.next_hand();
+    assert_eq!(yama[..8], [131, 12, 126, 29, 73, 85, 18, 56]);
+    assert_eq!(yama[132..], [59, 6, 112, 123]);
+    assert_eq!(dice, [2, 3]);
+
+    let mut sorted_yama = yama.clone();
+    sorted_yama.sort();
+    assert_eq!(sorted_yama, (0..NUM_GAME_TILES).collect::<Vec<usize>>());
+
+    let (yama, dice) = wall.next_hand();
+    assert_eq!(yama[..8], [131, 14, 80, 126, 135, 81, 85, 83]);
+    assert_eq!(dice, [2, 6]);
+
+    assert_eq!(tile_from_tenhou_id(0, 1), Tile::man_tile(1));
+    assert_eq!(tile_from_tenhou_id(16, 1), Tile { suit : Suit::Man, value : SuitVal::Five, red : true });
+    assert_eq!(tile_from_tenhou_id(17, 1), Tile::man_tile(5));
+    assert_eq!(tile_from_tenhou_id(52, 0), Tile::pin_tile(5));
+    assert_eq!(tile_from_tenhou_id(135, 1), Tile { suit : Suit::Honor, value : SuitVal::Red, red : false });
+}
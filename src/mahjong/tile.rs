@@ -221,6 +221,65 @@ impl Tile {
             None => None
         }
     }
+
+    pub fn is_terminal(&self) -> bool
+    {
+        self.suit != Suit::Honor && (self.value == SuitVal::One || self.value == SuitVal::Nine)
+    }
+
+    pub fn is_honor_or_terminal(&self) -> bool
+    {
+        self.suit == Suit::Honor || self.is_terminal()
+    }
+
+    pub fn is_dragon(&self) -> bool
+    {
+        self.suit == Suit::Honor && (self.value == SuitVal::Red || self.value == SuitVal::White || self.value == SuitVal::Green)
+    }
+
+    /// this tile's position in all_tile_kinds(). Red fives are the same kind as the other fives
+    pub fn kind_idx(&self) -> usize
+    {
+        match self.suit {
+            Suit::Man => self.value as usize - 1,
+            Suit::Pin => self.value as usize + 8,
+            Suit::Sou => self.value as usize + 17,
+            Suit::Honor => 27 + HONOR_VALUES.iter().position(|value| *value == self.value).unwrap(),
+        }
+    }
+
+    pub fn from_kind_idx(kind_idx : usize) -> Tile
+    {
+        match kind_idx / 9 {
+            0 => Tile::man_tile(kind_idx % 9 + 1),
+            1 => Tile::pin_tile(kind_idx % 9 + 1),
+            2 => Tile::sou_tile(kind_idx % 9 + 1),
+            _ => Tile { suit : Suit::Honor, value : HONOR_VALUES[kind_idx - 27], red : false },
+        }
+    }
+
+    /// returns the dora tile when this tile is a dora indicator. Numbers wrap from 9 back to 1,
+    /// winds go East -> South -> West -> North -> East, and dragons go White -> Green -> Red -> White
+    pub fn indicated_dora(&self) -> Tile
+    {
+        let value = match self.value {
+            SuitVal::Nine => SuitVal::One,
+            SuitVal::East => SuitVal::South,
+            SuitVal::South => SuitVal::West,
+            SuitVal::West => SuitVal::North,
+            SuitVal::North => SuitVal::East,
+            SuitVal::White => SuitVal::Green,
+            SuitVal::Green => SuitVal::Red,
+            SuitVal::Red => SuitVal::White,
+            number => number.get_next_num().unwrap(),
+        };
+
+        Tile {
+            suit : self.suit,
+            value,
+            red : false,
+        }
+    }
 }
 
 /// Tiles should hash the same regardless of whether they are red or not
@@ -248,6 +307,8 @@ impl fmt::Display for Tile {
 }
 
 pub const INVALID_TILE : Tile = Tile { suit : Suit::Man, value : SuitVal::East, red : true };
+/// set aside as nuki dora in sanma
+pub const NORTH_TILE : Tile = Tile { suit : Suit::Honor, value : SuitVal::North, red : false };
 
 pub fn print_tiles(tiles : &[Tile], num_to_print : usize) -> ()
 {
@@ -454,6 +515,143 @@ pub fn find_possible_sets_with_tile(tile : Tile, hand_without_tile : &[Tile]) ->
     return possible_sets;
 }
 
+/// number of distinct tiles, ignoring red fives
+pub const NUM_TILE_KINDS : usize = 34;
+
+const HONOR_VALUES : [SuitVal; 7] = [SuitVal::East, SuitVal::South, SuitVal::West, SuitVal::North, SuitVal::White, SuitVal::Green, SuitVal::Red];
+
+/// how many of each kind of tile there are, indexed by Tile::kind_idx
+pub fn tile_counts(tiles : &[Tile]) -> [u8; NUM_TILE_KINDS]
+{
+    let mut counts = [0; NUM_TILE_KINDS];
+    for tile in tiles
+    {
+        counts[tile.kind_idx()] += 1;
+    }
+
+    counts
+}
+
+/// every distinct tile, ignoring red fives. Numbered tiles by suit, followed by the winds and dragons
+pub fn all_tile_kinds() -> Vec<Tile>
+{
+    let mut tiles : Vec<Tile> = vec![];
+
+    for suit in [Suit::Man, Suit::Pin, Suit::Sou]
+    {
+        for number_val in 1..=9
+        {
+            tiles.push(Tile::numbered_tile(suit, number_val));
+        }
+    }
+
+    for value in HONOR_VALUES
+    {
+        tiles.push(Tile { suit : Suit::Honor, value, red : false });
+    }
+
+    tiles
+}
+
+/// true if a sorted group of 14 tiles is seven different pairs
+pub fn is_seven_pairs(tiles : &[Tile]) -> bool
+{
+    tiles.len() == 14
+    && tiles.chunks(2).all(|pair| pair[0] == pair[1])
+    && tiles.chunks(2).zip(tiles.chunks(2).skip(1)).all(|(pair, next_pair)| pair[0] != next_pair[0])
+}
+
+/// Finds every way a sorted group of tiles can be split into exactly one pair, with the rest being triplets and sequences.
+/// Returns an empty vector if the tiles can't be split that way.
+pub fn find_complete_hand_sets(tiles : &[Tile]) -> Vec<Vec<Set>>
+{
+    let mut complete_hands : Vec<Vec<Set>> = vec![];
+
+    if tiles.len() % 3 != 2
+    {
+        return complete_hands;
+    }
+
+    let mut seen_pairs : Vec<Tile> = vec![];
+
+    for (i, tile) in tiles.iter().enumerate()
+    {
+        if seen_pairs.contains(tile)
+        {   continue;   }
+
+        let second_idx = match tiles[(i + 1)..].iter().position(|other| *other == *tile) {
+            Some(offset) => i + 1 + offset,
+            None => continue,
+        };
+
+        seen_pairs.push(*tile);
+
+        let mut remaining_tiles = tiles.to_vec();
+        remaining_tiles.remove(second_idx);
+        remaining_tiles.remove(i);
+
+        for mut sets in find_triplets_and_sequences(&remaining_tiles)
+        {
+            sets.insert(0, Set { set_type : SetType::Pair, tiles : vec![tiles[i], tiles[second_idx]] });
+            complete_hands.push(sets);
+        }
+    }
+
+    complete_hands
+}
+
+/// splits sorted tiles entirely into triplets and sequences. Since the leftmost tile always has to be the start of a
+/// triplet or a sequence, each arrangement is only found once
+fn find_triplets_and_sequences(tiles : &[Tile]) -> Vec<Vec<Set>>
+{
+    if tiles.is_empty()
+    {
+        return vec![vec![]];
+    }
+
+    let mut arrangements : Vec<Vec<Set>> = vec![];
+    let first_tile = tiles[0];
+
+    if tiles.iter().filter(|tile| **tile == first_tile).count() >= 3
+    {
+        let mut remaining_tiles = tiles.to_vec();
+        let triplet_tiles : Vec<Tile> = remaining_tiles.drain(0..3).collect();
+
+        for mut sets in find_triplets_and_sequences(&remaining_tiles)
+        {
+            sets.insert(0, Set { set_type : SetType::Triplet, tiles : triplet_tiles.clone() });
+            arrangements.push(sets);
+        }
+    }
+
+    if let Some(second_tile) = first_tile.get_next_num_tile()
+    {
+        if let Some(third_tile) = second_tile.get_next_num_tile()
+        {
+            let second_idx = tiles.iter().position(|tile| *tile == second_tile);
+            let third_idx = tiles.iter().position(|tile| *tile == third_tile);
+
+            if let (Some(second_idx), Some(third_idx)) = (second_idx, third_idx)
+            {
+                let sequence_tiles = vec![first_tile, tiles[second_idx], tiles[third_idx]];
+
+                let mut remaining_tiles = tiles.to_vec();
+                remaining_tiles.remove(third_idx);
+                remaining_tiles.remove(second_idx);
+                remaining_tiles.remove(0);
+
+                for mut sets in find_triplets_and_sequences(&remaining_tiles)
+                {
+                    sets.insert(0, Set { set_type : SetType::Sequence, tiles : sequence_tiles.clone() });
+                    arrangements.push(sets);
+                }
+            }
+        }
+    }
+
+    arrangements
+}
+
 
 
 
@@ -542,13 +740,84 @@ pub fn get_callable_chii_combinations_with_tile(hand : &[Tile], tile : Tile) ->
 // ----------------------------------------------------------------------------------------
 // ----------------------------------------------------------------------------------------
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum WaitType {
     Ryanmen, // double sided sequence
     Penchan, // One sided wait of sequence (sequence has terminal)
     Shanpon, // either of two pairs to form a triplet
     Kanchan, // middle of sequence
     Tanki, // pair wait
+    KokushiThirteenSided, // thirteen orphans waiting on any of the thirteen tiles
+    ChuurenNineSided, // nine gates waiting on any of the nine tiles of its suit
+}
+
+impl WaitType {
+    /// Returns the wait that was completed when winning_tile finished winning_set
+    pub fn from_winning_set(winning_set : &Set, winning_tile : Tile) -> WaitType
+    {
+        match winning_set.set_type {
+            SetType::Pair => WaitType::Tanki,
+            SetType::Triplet | SetType::Kan => WaitType::Shanpon,
+            SetType::Sequence => {
+                let mut sequence = winning_set.tiles.clone();
+                sequence.sort();
+
+                if sequence[1] == winning_tile
+                {   WaitType::Kanchan   }
+                else if (sequence[0] == winning_tile && sequence[0].value == SuitVal::Seven)
+                    || (sequence[2] == winning_tile && sequence[2].value == SuitVal::Three)
+                {   WaitType::Penchan   }
+                else
+                {   WaitType::Ryanmen   }
+            }
+        }
+    }
+}
+
+/// One way of reading a complete hand as sets. A hand can be read more than one way (111222333 can be three triplets
+/// or three sequences), and should be scored as whichever way is worth the most.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HandInterpretation {
+    /// sets made entirely from the player's own tiles, which includes the pair and any closed kans
+    pub concealed_sets : Vec<Set>,
+    /// sets that used another player's tile. Called sets, and the set completed by a ron
+    pub open_sets : Vec<Set>,
+    /// the set the winning tile completed. It's also in either concealed_sets or open_sets
+    pub winning_set : Set,
+    pub wait : WaitType,
+}
+
+impl HandInterpretation {
+    pub fn all_sets(&self) -> impl Iterator<Item = &Set>
+    {
+        self.concealed_sets.iter().chain(self.open_sets.iter())
+    }
+
+    pub fn all_tiles(&self) -> impl Iterator<Item = &Tile>
+    {
+        self.all_sets().flat_map(|set| set.tiles.iter())
+    }
+
+    pub fn is_seven_pairs(&self) -> bool
+    {
+        self.all_sets().count() == 7 && self.all_sets().all(|set| set.set_type == SetType::Pair)
+    }
+
+    pub fn pair(&self) -> Option<&Set>
+    {
+        self.all_sets().find(|set| set.set_type == SetType::Pair)
+    }
+
+    pub fn sequences(&self) -> Vec<&Set>
+    {
+        self.all_sets().filter(|set| set.set_type == SetType::Sequence).collect()
+    }
+
+    /// triplets and kans
+    pub fn triplets(&self) -> Vec<&Set>
+    {
+        self.all_sets().filter(|set| set.set_type == SetType::Triplet || set.set_type == SetType::Kan).collect()
+    }
 }
 
 /// Designates which call is used, and also contains
@@ -569,8 +838,9 @@ impl CallTypes {
     pub fn precedence(&self) -> usize {
         match self {
             CallTypes::Tsumo => 4,
-            CallTypes::AddedKan => 3, //TODO: If a player calls on kokushi musou, addedkan shouldn't have higher precedence
-            CallTypes::Ron(_) => 2,
+            CallTypes::Ron(_) => 3,
+            CallTypes::AddedKan => 2, // a ron on the added tile (chankan) robs the kan
+
             CallTypes::Pon | CallTypes::OpenKan | CallTypes::ClosedKan => 1,
             CallTypes::Chii => 0,
         }
@@ -613,131 +883,114 @@ impl Default for Calls {
     }
 }
 
-/// Takes a hand in tenpai, and returns the tiles which would complete the hand and give it a win
-/// winning_configurations contains possible combinations of sets which can be made from a player which only need one more set to win
-/// TODO: Make compatible with yakuman, and not just basic complete hands
-pub fn get_winning_tiles_from_tenpai_hand(hand : &Vec<Tile>, winning_configurations : Vec<Vec<Set>>) -> Vec<(Tile, Set)>
-{
-    let mut winning_tiles : Vec<(Tile, Set)> = vec![];
+/// One tile a tenpai hand can win on, with one way of reading the completed hand
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Wait {
+    pub winning_tile : Tile,
+    /// the sets of the completed hand, not counting called sets. Empty for kokushi, which isn't made of sets
+    pub sets : Vec<Set>,
+    /// the set the winning tile completed. None for kokushi
+    pub winning_set : Option<Set>,
+    pub wait_type : WaitType,
+}
 
-    for winning_sets in winning_configurations
+impl Wait {
+    /// the set taken from the discarder when calling ron. Kokushi only takes the tile itself
+    pub fn ron_set(&self) -> Set
     {
-        let mut found_tile_map = vec![false ; hand.len()];
-
-        // map tiles used in sets - to tiles in the hand, in order to find which tiles aren't used by the winning configurations
-        for set in winning_sets
-        {
-            for tile in &set.tiles
-            {
-                let idx = hand.iter().position(|hand_tile| *hand_tile == *tile);
-                if idx.is_none()
-                {
-                    continue;
-                }
-                
-                let mut idx = idx.unwrap();
+        match &self.winning_set {
+            Some(winning_set) => winning_set.clone(),
+            None => Set { set_type : SetType::Pair, tiles : vec![self.winning_tile] },
+        }
+    }
+}
 
-                // since there can be duplicates of tiles, move past the ones we've already found from previous sets
-                while found_tile_map[idx] == true
-                {   idx += 1;   }
+/// true if thirteen orphans is one of every terminal and honor, plus one more of them
+pub fn is_kokushi(tiles : &[Tile]) -> bool
+{
+    let mut kinds = tiles.to_vec();
+    kinds.sort();
+    kinds.dedup();
 
-                if hand[idx] != *tile // sanity check
-                {   panic!("Wtf?");  }
+    tiles.len() == 14 && kinds.len() == 13 && tiles.iter().all(|tile| tile.is_honor_or_terminal())
+}
 
-                found_tile_map[idx] = true;
-            }
-        }
+/// true if the tiles are exactly 1112345678999 of one suit, which wins on any tile of that suit
+pub fn is_nine_sided_chuuren(tiles : &[Tile]) -> bool
+{
+    let counts = tile_counts(tiles);
 
-        let first_unclaimed_tile_pos = found_tile_map.iter().position(|check_bool| *check_bool == false).expect("Error: No unclaimed tile in hand");
+    tiles.len() == 13 && tiles[0].suit != Suit::Honor && tiles.iter().all(|tile| tile.suit == tiles[0].suit)
+    && (0..9).all(|number_idx| counts[tiles[0].kind_idx() - (tiles[0].value as usize - 1) + number_idx] == if number_idx == 0 || number_idx == 8 { 3 } else { 1 })
+}
 
-        // TODO: this is because there's no way to get the position of a second element like the first above. Very ugly, might need to change.
-        let mut tmp_idx = first_unclaimed_tile_pos + 1;
-        let second_unclaimed_tile_pos = loop {
-            if tmp_idx >= found_tile_map.len() { break None; }
-            else if found_tile_map[tmp_idx] == false { break Some(tmp_idx); }
-            else { tmp_idx += 1; }
-        };
+/// true if the tiles are a winning shape: four sets and a pair (counting called sets), seven pairs, or thirteen orphans
+pub fn is_complete_hand(tiles : &[Tile]) -> bool
+{
+    let mut tiles = tiles.to_vec();
+    tiles.sort();
 
+    is_seven_pairs(&tiles) || is_kokushi(&tiles) || ! find_complete_hand_sets(&tiles).is_empty()
+}
 
-        // Not waiting on pair, need to find which tile completes the set
-        if let Some(second_unclaimed_tile_pos) = second_unclaimed_tile_pos
-        {
-            println!("This was added for testing. {} pos idx", second_unclaimed_tile_pos);
-            let first_tile = hand[first_unclaimed_tile_pos];
-            let second_tile = hand[second_unclaimed_tile_pos];
+/// Every tile the concealed tiles of a tenpai hand can win on, once for each way of reading the completed hand.
+/// Called sets aren't included in the tiles. A tile the hand already holds all four of can't be won on
+pub fn find_waits(tiles : &[Tile]) -> Vec<Wait>
+{
+    let mut waits : Vec<Wait> = vec![];
+    let counts = tile_counts(tiles);
+    let nine_sided_chuuren = is_nine_sided_chuuren(tiles);
 
-            // need a triplet to complete the hand
-            if first_tile == second_tile
-            {
-                winning_tiles.push((first_tile, Set {
-                    set_type : SetType::Triplet,
-                    tiles : vec![first_tile ; 3],
-                }));
-            }
-            // need a sequence to complete the hand
-            else if first_tile.get_next_num_tile().unwrap_or(INVALID_TILE) == second_tile || first_tile.get_prev_num_tile().unwrap_or(INVALID_TILE) == second_tile
-            {
-                let prev_in_seq = if first_tile < second_tile { first_tile } else { second_tile };
-                let next_in_seq = if first_tile < second_tile { second_tile } else { first_tile };
+    for winning_tile in all_tile_kinds()
+    {
+        if counts[winning_tile.kind_idx()] >= 4
+        {   continue;   }
 
-                let prev_prev_for_seq = prev_in_seq.get_prev_num_tile();
-                let next_next_for_seq = next_in_seq .get_next_num_tile();
+        let mut completed_tiles = tiles.to_vec();
+        completed_tiles.push(winning_tile);
+        completed_tiles.sort();
 
-                if prev_prev_for_seq.is_none() && next_next_for_seq.is_none()
-                {
-                    panic!("Error: Winning tile was needed for sequence, but instead was not possible. Tiles are {} and {}", prev_in_seq, next_in_seq);
-                }
+        if is_kokushi(&completed_tiles)
+        {
+            // with all thirteen orphans already in the hand, any of them makes the pair
+            waits.push(Wait {
+                winning_tile,
+                sets : vec![],
+                winning_set : None,
+                wait_type : if counts[winning_tile.kind_idx()] == 1 { WaitType::KokushiThirteenSided } else { WaitType::Tanki },
+            });
+        }
 
-                if let Some(prev_prev_for_seq) = prev_prev_for_seq
-                {
-                    winning_tiles.push((prev_prev_for_seq, Set {
-                        set_type : SetType::Sequence,
-                        tiles : vec![prev_prev_for_seq, prev_in_seq, next_in_seq]
-                    }));
-                }
+        if is_seven_pairs(&completed_tiles)
+        {
+            waits.push(Wait {
+                winning_tile,
+                sets : completed_tiles.chunks(2).map(Set::from_tiles).collect(),
+                winning_set : Some(Set { set_type : SetType::Pair, tiles : vec![winning_tile; 2] }),
+                wait_type : WaitType::Tanki,
+            });
+        }
 
-                if let Some(next_next_for_seq) = next_next_for_seq
+        for sets in find_complete_hand_sets(&completed_tiles)
+        {
+            for winning_set in sets.iter().filter(|set| set.tiles.contains(&winning_tile))
+            {
+                let wait = Wait {
+                    winning_tile,
+                    sets : sets.clone(),
+                    winning_set : Some(winning_set.clone()),
+                    wait_type : if nine_sided_chuuren { WaitType::ChuurenNineSided } else { WaitType::from_winning_set(winning_set, winning_tile) },
+                };
+
+                if ! waits.contains(&wait)
                 {
-                    winning_tiles.push((next_next_for_seq, Set{
-                        set_type : SetType::Sequence,
-                        tiles : vec![prev_in_seq, next_in_seq, next_next_for_seq]
-                    }));
+                    waits.push(wait);
                 }
             }
-            // waiting on two pairs
-            else
-            {
-                winning_tiles.push(
-                    (first_tile, Set {
-                        set_type : SetType::Pair,
-                        tiles : vec![first_tile ; 2],
-                    }
-                    )
-                );
-
-                winning_tiles.push(
-                    (second_tile, Set {
-                        set_type : SetType::Pair,
-                        tiles : vec![second_tile ; 2],
-                    })
-                );
-            }
-        }
-        // waiting on pair
-        else
-        {
-            // push the tile not in any set to the winning tiles. We need a pair with that tile
-            let pair_tile = hand[first_unclaimed_tile_pos];
-
-            winning_tiles.push((pair_tile, Set {
-                set_type : SetType::Pair,
-                tiles : vec![pair_tile ; 2]
-            }));
         }
-
     }
 
-    return winning_tiles;
+    waits
 }
 
 
@@ -775,67 +1028,135 @@ pub fn get_winning_tiles_from_tenpai_hand(hand : &Vec<Tile>, winning_configurati
 // ----------------------------------------------------------------------------------------
 
 #[test]
-fn test_get_winning_tiles_from_tenpai_hand()
+fn test_find_waits()
 {
+    fn winning_tiles(waits : &[Wait]) -> Vec<Tile>
     {
-        let hand = vec![Tile { suit : Suit::Pin, value : SuitVal::Eight, red : false}, Tile { suit : Suit::Sou, value : SuitVal::Five, red : false }];
-        let called_sets = vec![vec![
-            Set::sequence( Tile::man_tile(1) ),
-            Set::sequence( Tile::man_tile(7) ),
-            Set::sequence( Tile::pin_tile(1) ),
-            Set::sequence( Tile::pin_tile(1) ),
-        ]];
-
-        let tiles_and_sets = get_winning_tiles_from_tenpai_hand(&hand, called_sets);
-
-        assert_eq!(tiles_and_sets.len(), 2);
-        println!("{:?}", tiles_and_sets);
-        assert_eq!((tiles_and_sets[0].0 == hand[0]), true);
-        assert_eq!((tiles_and_sets[1].0 == hand[1]), true);
+        let mut tiles : Vec<Tile> = waits.iter().map(|wait| wait.winning_tile).collect();
+        tiles.dedup();
+        tiles
     }
-    
-    {
-        let hand = vec![ Tile::pin_tile(7), Tile::pin_tile(8), Tile::man_tile(2), Tile::man_tile(2) ];
-        let called_sets = vec![vec![
-            Set::sequence(Tile::sou_tile(1)),
-            Set::triplet(Tile::sou_tile(7)),
-            Set::sequence(Tile::pin_tile(1)),
-        ]];
-            
-        let tiles_and_sets = get_winning_tiles_from_tenpai_hand(&hand, called_sets);
 
-        assert_eq!(tiles_and_sets.len(), 2);
-        println!("{:?}", tiles_and_sets);
-        assert_eq!((tiles_and_sets[0].0 == Tile::pin_tile(6)), true);
-        assert_eq!((tiles_and_sets[1].0 == Tile::pin_tile(9)), true);
-    }
-    
+    let east = Tile { suit : Suit::Honor, value : SuitVal::East, red : false };
+
+    // single wait on the pair
+    let hand = vec![
+        Tile::man_tile(1), Tile::man_tile(2), Tile::man_tile(3), Tile::man_tile(7), Tile::man_tile(8), Tile::man_tile(9),
+        Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3), Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3),
+        Tile::pin_tile(8),
+    ];
+    let waits = find_waits(&hand);
+    assert_eq!(waits.len(), 1);
+    assert_eq!(waits[0].winning_tile, Tile::pin_tile(8));
+    assert_eq!(waits[0].wait_type, WaitType::Tanki);
+    assert_eq!(waits[0].ron_set(), Set { set_type : SetType::Pair, tiles : vec![Tile::pin_tile(8); 2] });
+
+    // two sided wait
+    let hand = vec![
+        Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(7), Tile::sou_tile(7), Tile::sou_tile(7),
+        Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3), Tile::pin_tile(7), Tile::pin_tile(8),
+        Tile::man_tile(2), Tile::man_tile(2),
+    ];
+    let waits = find_waits(&hand);
+    assert_eq!(winning_tiles(&waits), vec![Tile::pin_tile(6), Tile::pin_tile(9)]);
+    assert!(waits.iter().all(|wait| wait.wait_type == WaitType::Ryanmen));
+    assert_eq!(waits[0].ron_set(), Set::sequence(Tile::pin_tile(6)));
+
+    // 5678 waits on either end for the pair
+    let hand = vec![
+        Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(7), Tile::sou_tile(7), Tile::sou_tile(7),
+        Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3),
+        Tile::pin_tile(5), Tile::pin_tile(6), Tile::pin_tile(7), Tile::pin_tile(8),
+    ];
+    let waits = find_waits(&hand);
+    assert_eq!(winning_tiles(&waits), vec![Tile::pin_tile(5), Tile::pin_tile(8)]);
+    assert!(waits.iter().all(|wait| wait.wait_type == WaitType::Tanki));
+
+    // 6777 waits on 5 and 8 to finish a sequence, or on 6 to finish the pair
+    let hand = vec![
+        Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(7), Tile::sou_tile(7), Tile::sou_tile(7),
+        Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3),
+        Tile::man_tile(6), Tile::man_tile(7), Tile::man_tile(7), Tile::man_tile(7),
+    ];
+    let waits = find_waits(&hand);
+    assert_eq!(winning_tiles(&waits), vec![Tile::man_tile(5), Tile::man_tile(6), Tile::man_tile(8)]);
+    for wait in &waits
     {
-        let hand = vec![ Tile::pin_tile(5), Tile::pin_tile(6), Tile::pin_tile(7), Tile::pin_tile(8) ];
-        let called_sets = vec![
-            Set::sequence(Tile::sou_tile(1)),
-            Set::triplet(Tile::sou_tile(7)),
-            Set::sequence(Tile::pin_tile(1)),
-        ];
-
-        let mut first_winning = called_sets.clone();
-        first_winning.push( Set::sequence( Tile::pin_tile(5) ) );
-
-        let mut second_winning = called_sets.clone();
-        second_winning.push( Set::sequence( Tile::pin_tile(6) ) );
-
-
-        let winning_configurations = vec![
-            first_winning,
-            second_winning
-        ];
+        let expected_wait_type = if wait.winning_tile == Tile::man_tile(6) { WaitType::Tanki } else { WaitType::Ryanmen };
+        assert_eq!(wait.wait_type, expected_wait_type);
+    }
 
-        let tiles_and_sets = get_winning_tiles_from_tenpai_hand(&hand, winning_configurations);
+    // seven pairs waits on the single tile
+    let hand = vec![
+        Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(5), Tile::man_tile(5),
+        Tile::pin_tile(2), Tile::pin_tile(2), Tile::pin_tile(7), Tile::pin_tile(7),
+        Tile::sou_tile(3), Tile::sou_tile(3), Tile::sou_tile(9), Tile::sou_tile(9),
+        east,
+    ];
+    let waits = find_waits(&hand);
+    assert_eq!(waits.len(), 1);
+    assert_eq!(waits[0].winning_tile, east);
+    assert_eq!(waits[0].wait_type, WaitType::Tanki);
+    assert_eq!(waits[0].sets.len(), 7);
+
+    // thirteen orphans with one of each waits on all thirteen
+    let kokushi_hand : Vec<Tile> = all_tile_kinds().into_iter().filter(|tile| tile.is_honor_or_terminal()).collect();
+    let waits = find_waits(&kokushi_hand);
+    assert_eq!(waits.len(), 13);
+    assert!(waits.iter().all(|wait| wait.wait_type == WaitType::KokushiThirteenSided && wait.winning_set.is_none()));
+    assert_eq!(waits[0].ron_set().tiles, vec![waits[0].winning_tile]);
+
+    // and with a pair only waits on the missing tile
+    let mut kokushi_hand = kokushi_hand;
+    kokushi_hand.retain(|tile| *tile != east);
+    kokushi_hand.push(Tile::man_tile(1));
+    let waits = find_waits(&kokushi_hand);
+    assert_eq!(waits.len(), 1);
+    assert_eq!(waits[0].winning_tile, east);
+    assert_eq!(waits[0].wait_type, WaitType::Tanki);
+
+    // nine gates waits on every tile of its suit
+    let mut chuuren_hand = vec![Tile::pin_tile(1), Tile::pin_tile(1), Tile::pin_tile(9), Tile::pin_tile(9)];
+    chuuren_hand.extend((1..=9).map(Tile::pin_tile));
+    let waits = find_waits(&chuuren_hand);
+    assert_eq!(winning_tiles(&waits), (1..=9).map(Tile::pin_tile).collect::<Vec<Tile>>());
+    assert!(waits.iter().all(|wait| wait.wait_type == WaitType::ChuurenNineSided));
+
+    // a tile the hand holds all four of can't be won on
+    let hand = vec![
+        Tile::sou_tile(1), Tile::sou_tile(2), Tile::sou_tile(3), Tile::sou_tile(7), Tile::sou_tile(7), Tile::sou_tile(7),
+        Tile::pin_tile(1), Tile::pin_tile(2), Tile::pin_tile(3),
+        Tile::man_tile(2), Tile::man_tile(2), Tile::man_tile(2), Tile::man_tile(2),
+    ];
+    assert!(find_waits(&hand).iter().all(|wait| wait.winning_tile != Tile::man_tile(2)));
+}
 
-        assert_eq!(tiles_and_sets.len(), 2);
-        println!("{:#?}", tiles_and_sets);
-        assert_eq!(tiles_and_sets[0].0, Tile::pin_tile(8));
-        assert_eq!(tiles_and_sets[1].0, Tile::pin_tile(5));
-    }
+#[test]
+fn test_indicated_dora()
+{
+    assert_eq!(Tile::man_tile(3).indicated_dora(), Tile::man_tile(4));
+    assert_eq!(Tile::sou_tile(9).indicated_dora(), Tile::sou_tile(1));
+    assert_eq!(Tile { suit : Suit::Honor, value : SuitVal::North, red : false }.indicated_dora(), Tile { suit : Suit::Honor, value : SuitVal::East, red : false });
+    assert_eq!(Tile { suit : Suit::Honor, value : SuitVal::Red, red : false }.indicated_dora(), Tile { suit : Suit::Honor, value : SuitVal::White, red : false });
+
+    // a red five indicator still points at a plain six
+    let red_five = Tile { suit : Suit::Pin, value : SuitVal::Five, red : true };
+    assert!(! red_five.indicated_dora().red);
+}
 
-}
\ No newline at end of file
+#[test]
+fn test_is_seven_pairs()
+{
+    let mut tiles = vec![
+        Tile::man_tile(1), Tile::man_tile(1), Tile::man_tile(5), Tile::man_tile(5),
+        Tile::pin_tile(2), Tile::pin_tile(2), Tile::pin_tile(7), Tile::pin_tile(7),
+        Tile::sou_tile(3), Tile::sou_tile(3), Tile::sou_tile(9), Tile::sou_tile(9),
+        Tile { suit : Suit::Honor, value : SuitVal::Red, red : false }, Tile { suit : Suit::Honor, value : SuitVal::Red, red : false },
+    ];
+    assert!(is_seven_pairs(&tiles));
+
+    // four of a kind isn't two pairs
+    tiles[2] = Tile::man_tile(1);
+    tiles[3] = Tile::man_tile(1);
+    assert!(! is_seven_pairs(&tiles));
+}
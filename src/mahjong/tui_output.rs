@@ -189,13 +189,15 @@ pub fn output_row_view(game : &Game, player_idx : usize) -> ()
 
         // print game header
         println!("    Round Wind: {}", game.round_wind);
+        println!("    Dora Indicators: {}", game.dora_indicators().iter().map(|tile| tile.to_string()).collect::<Vec<String>>().join(" "));
+        println!("    Riichi Sticks: {}  Honba: {}", game.riichi_sticks, game.honba);
         println!("-------------------------");
         println!();
 
 
-        for i in 0..NUM_PLAYERS
+        for i in 0..game.num_players()
         {
-            let loop_player = &game.players[(player_idx + i) % NUM_PLAYERS];
+            let loop_player = &game.players[(player_idx + i) % game.num_players()];
 
             // only reveal other player hands for debugging purposes
             let hand = if ! DEBUG_OUTPUT && ! loop_player.is_human {
@@ -208,10 +210,11 @@ pub fn output_row_view(game : &Game, player_idx : usize) -> ()
             
             let discard_pile = mahjong_tiles_strs(&loop_player.discard_pile, 1000);
 
-            println!("{}  Pts:{} Wind:{}   -- Tenpai:{}    {}",
+            println!("{}  Pts:{} Wind:{}   -- Tenpai:{}    {}{}",
             if *loop_player == game.players[game.curr_player_idx] { ACTIVE_PLAYER_MARKER } else { " " },
             loop_player.points, loop_player.seat_wind, if DEBUG_OUTPUT || loop_player.is_human { loop_player.tenpai.to_string() } else { String::from("N/A") },
-            if loop_player.furiten { "FURITEN" } else { " " });
+            if loop_player.furiten { "FURITEN" } else { " " },
+            if loop_player.nuki_dora > 0 { format!("    Nuki dora:{}", loop_player.nuki_dora) } else { String::new() });
 
             let empty_string = String::from("");
             if loop_player.is_human
@@ -263,10 +266,14 @@ pub fn output_player_perspective(game : &Game, player_idx : usize) -> ()
         // outputs one "line" of tiles with 3 lines of stdout
 
         // player vars for printing info
+        // the opposite seat is left empty in sanma
+        let empty_seat = Player { hand : vec![], discard_pile : vec![], ..Player::default() };
+        let num_players = game.num_players();
+
         let curr_player : &Player = &game.players[player_idx];
-        let right_player : &Player = &game.players[(player_idx + 1) % NUM_PLAYERS];
-        let opposite_player : &Player = &game.players[(player_idx + 2) % NUM_PLAYERS];
-        let left_player : &Player = &game.players[(player_idx + 3) % NUM_PLAYERS];
+        let right_player : &Player = &game.players[(player_idx + 1) % num_players];
+        let opposite_player : &Player = if game.is_sanma() { &empty_seat } else { &game.players[(player_idx + 2) % num_players] };
+        let left_player : &Player = &game.players[(player_idx + num_players - 1) % num_players];
 
         let curr_discard_strs = mahjong_tiles_strs(&curr_player.discard_pile, TILES_IN_DISCARD_ROW * 4);
         let opposite_discard_strs = mahjong_tiles_strs(&opposite_player.discard_pile, TILES_IN_DISCARD_ROW * 4);
@@ -277,7 +284,7 @@ pub fn output_player_perspective(game : &Game, player_idx : usize) -> ()
         let opposite_hand = &mahjong_tiles_strs(&vec![INVALID_TILE ; opposite_player.hand.len()], 1000);
 
         // print top player
-        println!("{: ^SCREEN_WIDTH$}", format!("pts:{} wind:{}", opposite_player.points, opposite_player.seat_wind) );
+        println!("{: ^SCREEN_WIDTH$}", if game.is_sanma() { String::new() } else { format!("pts:{} wind:{}", opposite_player.points, opposite_player.seat_wind) });
         println!("{: ^SCREEN_WIDTH$}", opposite_hand[0]);
         println!("{: ^SCREEN_WIDTH$}", opposite_hand[1]);
         println!("{: ^SCREEN_WIDTH$}", opposite_hand[2]);
@@ -423,7 +430,7 @@ pub fn output_player_perspective(game : &Game, player_idx : usize) -> ()
 
 
 /// mutability of game is only for debug
-pub fn get_player_discard_idx(game : &mut Game, player_idx : usize, player_can_win : bool, player_can_close_kan : bool) -> DiscardChoices
+pub fn get_player_discard_idx(game : &mut Game, player_idx : usize, player_can_win : bool, player_can_kan : bool, player_can_riichi : bool, player_can_nuki_dora : bool) -> DiscardChoices
 {
             game.dump_game_state();
             output_game(game, player_idx);
@@ -453,7 +460,28 @@ pub fn get_player_discard_idx(game : &mut Game, player_idx : usize, player_can_w
                 }
             }
 
+            // a hand in riichi is locked, so the drawn tile is always discarded
+            if game.players[player_idx].is_in_riichi()
+            {
+                println!("You are in riichi, so the tile you drew is discarded. Press enter to continue");
+                std::io::stdin().read_line(&mut input).expect("stdin readline failed");
+                return DiscardChoices::DiscardTile(game.players[player_idx].hand.len() - 1);
+            }
+
             println!("Enter which tile you would like to discard (\"n\" standing for \"new\" works for the rightmost drawn tile)");
+            if player_can_riichi
+            {
+                println!("You can declare riichi. Type 'r' followed by the number of the tile to discard, for example \"r3\"");
+            }
+            if player_can_kan
+            {
+                println!("You can declare a kan. Type 'k' followed by the number of one of the tiles to kan, for example \"k3\"");
+            }
+            if player_can_nuki_dora
+            {
+                println!("You can set aside a north as nuki dora. Type \"kita\"");
+            }
+            println!("Type 'u' to see which discards leave you closest to a winning hand");
 
             std::io::stdin().read_line(&mut input).expect("stdin readline failed");
             input = input.trim().to_lowercase();
@@ -478,6 +506,39 @@ pub fn get_player_discard_idx(game : &mut Game, player_idx : usize, player_can_w
                 {
                     break DiscardChoices::DiscardTile(game.players[player_idx].hand.len() - 1);
                 }
+                else if player_can_riichi && input.starts_with('r')
+                {
+                    let discard_idx = input[1..].parse::<usize>().ok()
+                        .map(|tile_num| tile_num.wrapping_sub(1))
+                        .filter(|discard_idx| game.players[player_idx].tenpai_discard_idxs().contains(discard_idx));
+
+                    match discard_idx {
+                        Some(discard_idx) => break DiscardChoices::Riichi(discard_idx),
+                        None => println!("Discarding that tile wouldn't leave you in tenpai"),
+                    }
+                }
+                else if player_can_nuki_dora && input == "kita"
+                {
+                    break DiscardChoices::NukiDora;
+                }
+                else if input == "u"
+                {
+                    for line in discard_helper_strs(game, player_idx)
+                    {
+                        println!("{}", line);
+                    }
+                }
+                else if player_can_kan && input.starts_with('k')
+                {
+                    let kan_tile = input[1..].parse::<usize>().ok()
+                        .and_then(|tile_num| game.players[player_idx].hand.get(tile_num.wrapping_sub(1)).copied());
+
+                    match kan_tile {
+                        Some(kan_tile) if game.players[player_idx].closed_kan_tiles().contains(&kan_tile) => break DiscardChoices::OpenClosedKan(kan_tile),
+                        Some(kan_tile) if game.players[player_idx].added_kan_tiles().contains(&kan_tile) => break DiscardChoices::AddedKan(kan_tile),
+                        _ => println!("You can't kan with that tile"),
+                    }
+                }
                 else if input == "debug"
                 {
                     loop {
@@ -609,6 +670,19 @@ pub fn get_player_discard_idx(game : &mut Game, player_idx : usize, player_can_w
 }
 
 
+/// each discard with its shanten and the tiles it accepts, from best to worst. Tile numbers match the ones used to discard
+fn discard_helper_strs(game : &Game, player_idx : usize) -> Vec<String>
+{
+    game.player_discard_ukeire(player_idx).iter().map(|discard| {
+        let shanten = if discard.shanten == 0 { String::from("tenpai") } else { format!("{} shanten", discard.shanten) };
+        let accepted_tiles : Vec<String> = discard.accepted_tiles.iter()
+            .map(|(tile, num_left)| format!("{}x{}", tile, num_left))
+            .collect();
+
+        format!("Discard {} {}: {}, {} tiles - {}", discard.discard_idx + 1, discard.discard, shanten, discard.num_accepted(), accepted_tiles.join(" "))
+    }).collect()
+}
+
 pub fn get_player_call_choice(game : &Game, player_idx : usize, discarded_tile : Tile, all_possible_calls : &Vec<CalledSet>) -> Option<CalledSet>
 {
     game.dump_game_state();
@@ -716,7 +790,41 @@ pub fn get_player_call_choice(game : &Game, player_idx : usize, discarded_tile :
 
 
 
-pub fn output_player_win_or_lose(winning_player : &Player, human_is_playing : bool) -> ()
+/// Lines describing where a winning hand's points came from
+fn hand_score_strs(hand_score : &scoring::HandScore) -> Vec<String>
+{
+    let mut lines = vec![];
+
+    for (yaku, value) in &hand_score.hand_yaku
+    {
+        match hand_score.limit {
+            scoring::HandLimit::Yakuman(_) => lines.push(format!("{:?}: {} yakuman", yaku, value)),
+            _ => lines.push(format!("{:?}: {} han", yaku, value)),
+        }
+    }
+
+    if hand_score.dora + hand_score.aka_dora + hand_score.ura_dora > 0
+    {
+        lines.push(format!("Dora: {}  Aka dora: {}  Ura dora: {}", hand_score.dora, hand_score.aka_dora, hand_score.ura_dora));
+    }
+
+    if hand_score.honba > 0 || hand_score.riichi_sticks > 0
+    {
+        lines.push(format!("Honba: {}  Riichi sticks: {}", hand_score.honba, hand_score.riichi_sticks));
+    }
+
+    match hand_score.limit {
+        scoring::HandLimit::Yakuman(_) => lines.push(format!("{:?}", hand_score.limit)),
+        scoring::HandLimit::NoLimit => lines.push(format!("{} han {} fu", hand_score.han, hand_score.fu)),
+        _ => lines.push(format!("{} han {} fu - {:?}", hand_score.han, hand_score.fu, hand_score.limit)),
+    }
+
+    lines.push(format!("Point changes: {:?}", hand_score.point_changes));
+
+    lines
+}
+
+pub fn output_player_win_or_lose(winning_player : &Player, hand_score : &scoring::HandScore, human_is_playing : bool)
 {
     let you_win_str = vec!["__   __                   __        __  _           _   _   _   _   _   _",
                                     "\\ \\ / /   ___    _   _    \\ \\      / / (_)  _ __   | | | | | | | | | | | |",
@@ -754,6 +862,9 @@ pub fn output_player_win_or_lose(winning_player : &Player, human_is_playing : bo
 
     let winning_hand : Vec<&str> = winning_hand.iter().map(|string| string.as_str()).collect();
 
+    let score_lines = hand_score_strs(hand_score);
+    let score_lines : Vec<&str> = score_lines.iter().map(|string| string.as_str()).collect();
+
     let empty_string = "";
     let mut output_win_string_iter = std::iter::repeat(&empty_string).take(
         (SCREEN_HEIGHT - you_win_str.len()) / 2
@@ -763,7 +874,11 @@ pub fn output_player_win_or_lose(winning_player : &Player, human_is_playing : bo
                 std::iter::once(&"The winning had was").chain(
                     std::iter::once(&"----------------------").chain
                     (
-                        winning_hand.iter()
+                        winning_hand.iter().chain(
+                            std::iter::once(&empty_string).chain(
+                                score_lines.iter()
+                            )
+                        )
                     )
                 )
             )
@@ -782,3 +897,80 @@ pub fn output_player_win_or_lose(winning_player : &Player, human_is_playing : bo
         std::io::stdin().read_line(&mut worthless).expect("Stdin failed");
     }
 }
+
+/// asks the human player if they want to end the hand with kyuushu kyuuhai
+pub fn get_player_kyuushu_kyuuhai_choice(game : &Game, player_idx : usize) -> bool
+{
+    output_game(game, player_idx);
+    println!("You have nine different terminals and honors. Declare kyuushu kyuuhai and end the hand? Type 'y' for yes or 'n' for no");
+
+    loop {
+        let mut input = String::from("");
+        std::io::stdin().read_line(&mut input).expect("stdin readline failed");
+        input = input.trim().to_lowercase();
+
+        if input == "y"
+        {
+            return true;
+        }
+        else if input == "n"
+        {
+            return false;
+        }
+        else
+        {
+            println!("Please enter 'y' or 'n'");
+        }
+    }
+}
+
+pub fn output_abortive_draw(abortive_draw : AbortiveDrawType, human_is_playing : bool)
+{
+    println!("The hand ended in an abortive draw: {:?}", abortive_draw);
+
+    if human_is_playing
+    {
+        let mut worthless = String::from("");
+        std::io::stdin().read_line(&mut worthless).expect("Stdin failed");
+    }
+}
+
+fn format_thousands(points : i32) -> String
+{
+    format!("{:+.1}", points as f64 / 1000.0)
+}
+
+/// the final standings table shown at the end of the game
+pub fn final_results_strs(game : &Game, results : &Vec<scoring::FinalResult>) -> Vec<String>
+{
+    let mut lines = vec![
+        format!("{:<6} {:<10} {:>8} {:>8} {:>6} {:>6} {:>8}", "Place", "Player", "Points", "Return", "Oka", "Uma", "Final"),
+    ];
+
+    for result in results
+    {
+        let player = &game.players[result.player_idx];
+        lines.push(format!("{:<6} {:<10} {:>8} {:>8} {:>6} {:>6} {:>8}",
+            result.placement,
+            format!("{}{}", result.player_idx + 1, if player.is_human { " (You)" } else { "" }),
+            result.raw_score,
+            format_thousands(result.return_score),
+            format_thousands(result.oka),
+            format_thousands(result.uma),
+            format_thousands(result.final_score),
+        ));
+    }
+
+    lines
+}
+
+pub fn output_final_results(game : &Game, results : &Vec<scoring::FinalResult>)
+{
+    println!("Final results (seed {})", game.seed);
+    println!("-------------");
+
+    for line in final_results_strs(game, results)
+    {
+        println!("{}", line);
+    }
+}
@@ -12,11 +12,11 @@ pub fn round_up_to_100(points : i32) -> i32
 
 pub fn print_game_state(game : &Game) -> ()
 {
-    println!("Tiles ({} left to draw)\n--------------------------------", NUM_GAME_TILES - game.next_tile);
+    println!("Tiles ({} left to draw)\n--------------------------------", game.tiles.len() - game.next_tile);
     let mut i : usize = game.next_tile as usize;
 
     // print a row with 4 tiles on it
-    while i < NUM_GAME_TILES - 4 {
+    while i < game.tiles.len() - 4 {
         print!("{}:", i);
     
         for j in 0..4 {
@@ -28,29 +28,29 @@ pub fn print_game_state(game : &Game) -> ()
     }
 
     // print the last row with possibly less than 4 items
-    if i < NUM_GAME_TILES
+    if i < game.tiles.len()
     {
         print!("{}:", i);
 
-        while i < NUM_GAME_TILES {
+        while i < game.tiles.len() {
             print!("{},", game.tiles[i]);
             i += 1;
         }
     }
 
     println!("\nPlayers\n--------------------------------");
-    for i in 0..NUM_PLAYERS {
+    for i in 0..game.num_players() {
         print!("{} Player {}:{{{}pts}}", game.players[i as usize].seat_wind, i, game.players[i].points);
         print_tiles(&game.players[i as usize].hand, game.players[i as usize].hand.len());
     }
 
 }
 
-pub fn gen_random_suit(honors_available : bool) -> Suit
+pub fn gen_random_suit(rng : &mut impl Rng, honors_available : bool) -> Suit
     {
         let suit_range = if honors_available { 0..4 } else { 0..3 };
 
-        match rand::thread_rng().gen_range(suit_range) {
+        match rng.gen_range(suit_range) {
             0 => Suit::Man,
             1 => Suit::Pin,
             2 => Suit::Sou,
@@ -59,20 +59,20 @@ pub fn gen_random_suit(honors_available : bool) -> Suit
         }
     }
 
-pub fn get_random_pair_triplet_or_kan(num_tiles : usize) -> Set
+pub fn get_random_pair_triplet_or_kan(rng : &mut impl Rng, num_tiles : usize) -> Set
     {
-        let set_type = gen_random_suit(true);
+        let set_type = gen_random_suit(rng, true);
         let mut set_value;
 
         if set_type == Suit::Honor
         {
-            set_value = match rand::thread_rng().gen_range(0..7) {
+            set_value = match rng.gen_range(0..7) {
                 0 => SuitVal::East, 1 => SuitVal::West, 2 => SuitVal::South, 3 => SuitVal::North,
                 4 => SuitVal::Red, 5 => SuitVal::Green, 6 => SuitVal::White, _ => panic!()
             };
         }
         else {
-            set_value = match rand::thread_rng().gen_range(1..10) {
+            set_value = match rng.gen_range(1..10) {
                 1 => SuitVal::One, 2 => SuitVal::Two, 3 => SuitVal::Three, 4 => SuitVal::Four,
                 5 => SuitVal::Five, 6 => SuitVal::Six, 7 => SuitVal::Seven, 8 => SuitVal::Eight, 9 => SuitVal::Nine,
                 _ => panic!()
@@ -85,11 +85,11 @@ pub fn get_random_pair_triplet_or_kan(num_tiles : usize) -> Set
         }
     }
 
-    pub fn get_random_sequence() -> Set
+    pub fn get_random_sequence(rng : &mut impl Rng) -> Set
     {
-        let set_type = gen_random_suit(false);
+        let set_type = gen_random_suit(rng, false);
 
-        let number = match rand::thread_rng().gen_range(1..8) {
+        let number = match rng.gen_range(1..8) {
             1 => SuitVal::One, 2 => SuitVal::Two, 3 => SuitVal::Three, 4 => SuitVal::Four,
             5 => SuitVal::Five, 6 => SuitVal::Six, 7 => SuitVal::Seven, _ => panic!()
         };
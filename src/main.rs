@@ -2,17 +2,11 @@
 #![allow(unused_imports, dead_code)]
 
 
-// external modules
-extern crate strum;
-#[macro_use]
-extern crate strum_macros;
-
-
-// local modules
-pub mod mahjong;
-use mahjong::player::*;
-use mahjong::tile::*;
-use mahjong::*;
+// the game is in the library, so other crates can play it with their own AI strategies
+use mahjong::mahjong::player::*;
+use mahjong::mahjong::tile::*;
+use mahjong::mahjong::rules::*;
+use mahjong::mahjong::*;
 
 use utils::print_game_state;
 
@@ -23,12 +17,20 @@ use utils::print_game_state;
 
 
 fn main(){
-    let mut game = Game::default();
+    // passing a seed replays the same walls and AI choices. A seed from a Tenhou log replays that game's walls
+    let mut game = match std::env::args().nth(1) {
+        Some(seed) => match seed.parse::<u64>() {
+            Ok(seed) => Game::with_seed(RuleSet::tenhou(), seed),
+            Err(_) => Game::with_tenhou_seed(RuleSet::tenhou(), &seed).expect("The seed wasn't a number or a Tenhou seed"),
+        },
+        None => Game::new(RuleSet::tenhou()),
+    };
     game.human_is_playing = true;
-    game.play_game(2);
+    game.play_game();
 
 
     print_game_state(&game);
+    tui_output::output_final_results(&game, &scoring::final_results(&game));
 }
 
 